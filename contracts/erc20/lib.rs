@@ -1,13 +1,16 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-pub use self::erc20::Erc20;
+#[cfg(feature = "ierc20")]
+pub use self::erc20::IErc20;
+pub use self::erc20::{
+    AccessControl, BlackList, Erc20, Error, Ownable, PSP22Error, PSP22Metadata, PSP22Result,
+    Pausable, Result, RoleType, BLACKLISTER, BURNER, MINTER, PAUSER, PSP22,
+};
 use ink_lang as ink;
 
 #[ink::contract]
 mod erc20 {
-    use erc20_trait::{Error as IError, IErc20, Result as IResult};
-    use ink_prelude::string::String;
-    use ownership::Ownable;
+    use ink_prelude::{string::String, vec::Vec};
 
     #[cfg(not(feature = "ink-as-dependency"))]
     use ink_lang as ink;
@@ -15,7 +18,7 @@ mod erc20 {
     use ink_storage::{collections::HashMap as StorageHashMap, lazy::Lazy};
 
     /// The ERC-20 error types.
-    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     pub enum Error {
         /// Returned if not enough balance to fulfill a request is available.
@@ -28,11 +31,188 @@ mod erc20 {
         OnlyOwnerAccess,
         InvalidNewOwner,
         NotBlacklistedUser,
+        /// Returned if the token is paused.
+        Paused,
+        /// Returned if the caller lacks the role the message requires.
+        MissingRole,
+        /// Returned if tokens would be minted to the zero account.
+        ZeroAddress,
     }
 
     /// The ERC-20 result type.
     pub type Result<T> = core::result::Result<T, Error>;
 
+    /// The PSP22 error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum PSP22Error {
+        /// Returned for the token specific errors, named after the `Error` variant.
+        Custom(String),
+        /// Returned if not enough balance to fulfill a request is available.
+        InsufficientBalance,
+        /// Returned if not enough allowance to fulfill a request is available.
+        InsufficientAllowance,
+        /// Returned if the recipient is the zero account.
+        ZeroRecipientAddress,
+        /// Returned if the sender is the zero account.
+        ZeroSenderAddress,
+        /// Returned if the recipient contract rejects the transfer.
+        SafeTransferCheckFailed(String),
+    }
+
+    impl From<Error> for PSP22Error {
+        fn from(error: Error) -> Self {
+            let name = match error {
+                Error::InsufficientBalance => return PSP22Error::InsufficientBalance,
+                Error::InsufficientAllowance => return PSP22Error::InsufficientAllowance,
+                Error::InsufficientSupply => "InsufficientSupply",
+                Error::BlacklistedUser => "BlacklistedUser",
+                Error::InvalidAmount => "InvalidAmount",
+                Error::OnlyOwnerAccess => "OnlyOwnerAccess",
+                Error::InvalidNewOwner => "InvalidNewOwner",
+                Error::NotBlacklistedUser => "NotBlacklistedUser",
+                Error::Paused => "Paused",
+                Error::MissingRole => "MissingRole",
+                Error::ZeroAddress => "ZeroAddress",
+            };
+            PSP22Error::Custom(String::from(name))
+        }
+    }
+
+    /// The PSP22 result type.
+    pub type PSP22Result<T> = core::result::Result<T, PSP22Error>;
+
+    /// Identifier of a role of the access control.
+    pub type RoleType = u32;
+
+    /// Allowed to mint tokens.
+    pub const MINTER: RoleType = 1;
+    /// Allowed to burn tokens.
+    pub const BURNER: RoleType = 2;
+    /// Allowed to pause and unpause the token.
+    pub const PAUSER: RoleType = 3;
+    /// Allowed to manage the blacklist and destroy blacklisted funds.
+    pub const BLACKLISTER: RoleType = 4;
+
+    /// The PSP22 fungible token standard.
+    #[ink::trait_definition]
+    pub trait PSP22 {
+        /// Returns the total token supply.
+        #[ink(message)]
+        fn total_supply(&self) -> Balance;
+
+        /// Returns the account balance for the specified `owner`.
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance;
+
+        /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance;
+
+        /// Transfers `value` amount of tokens from the caller's account to account `to`.
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> PSP22Result<()>;
+
+        /// Transfers `value` tokens on the behalf of `from` to the account `to`.
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> PSP22Result<()>;
+
+        /// Allows `spender` to withdraw from the caller's account multiple times, up to
+        /// the `value` amount.
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: Balance) -> PSP22Result<()>;
+
+        /// Raises the allowance granted to `spender` by the caller by `delta_value`.
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> PSP22Result<()>;
+
+        /// Lowers the allowance granted to `spender` by the caller by `delta_value`.
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> PSP22Result<()>;
+    }
+
+    /// The optional PSP22 metadata.
+    #[ink::trait_definition]
+    pub trait PSP22Metadata {
+        /// Returns the token name.
+        #[ink(message)]
+        fn token_name(&self) -> Option<String>;
+
+        /// Returns the token symbol.
+        #[ink(message)]
+        fn token_symbol(&self) -> Option<String>;
+
+        /// Returns the token decimals.
+        #[ink(message)]
+        fn token_decimals(&self) -> u8;
+    }
+
+    /// The legacy ERC-20 interface, kept behind the `ierc20` feature for the clients still
+    /// using its selectors.
+    #[cfg(feature = "ierc20")]
+    #[ink::trait_definition]
+    pub trait IErc20 {
+        /// Creates a new ERC-20 contract with the specified initial supply.
+        #[ink(constructor)]
+        fn new(
+            initial_supply: Balance,
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: Option<u8>,
+        ) -> Self;
+
+        /// Returns the token name.
+        #[ink(message)]
+        fn token_name(&self) -> Option<String>;
+
+        /// Returns the token symbol.
+        #[ink(message)]
+        fn token_symbol(&self) -> Option<String>;
+
+        /// Returns the token decimals.
+        #[ink(message)]
+        fn token_decimals(&self) -> Option<u8>;
+
+        /// Returns the total token supply.
+        #[ink(message)]
+        fn total_supply(&self) -> Balance;
+
+        /// Returns the account balance for the specified `owner`.
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance;
+
+        /// Transfers `value` amount of tokens from the caller's account to account `to`.
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()>;
+
+        /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance;
+
+        /// Transfers `value` tokens on the behalf of `from` to the account `to`.
+        #[ink(message)]
+        fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<()>;
+
+        /// Allows `spender` to withdraw from the caller's account multiple times, up to
+        /// the `value` amount.
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()>;
+    }
+
     /// Base contract which allows children to implement an emergency stop mechanism.
     #[ink::trait_definition]
     pub trait Pausable {
@@ -68,6 +248,46 @@ mod erc20 {
         fn destroy_blackfunds(&mut self, blacklisted_user: AccountId) -> Result<()>;
     }
 
+    /// Basic authorization control, with ownership handed over in two steps.
+    #[ink::trait_definition]
+    pub trait Ownable {
+        /// Contract owner, `None` once the ownership is renounced.
+        #[ink(message)]
+        fn owner(&self) -> Option<AccountId>;
+
+        /// Account proposed as the next owner, if any.
+        #[ink(message)]
+        fn pending_owner(&self) -> Option<AccountId>;
+
+        /// Proposes `new_owner` as the next owner, who must accept the ownership.
+        #[ink(message)]
+        fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<()>;
+
+        /// Makes the pending owner the owner.
+        #[ink(message)]
+        fn accept_ownership(&mut self) -> Result<()>;
+
+        /// Leaves the contract without owner.
+        #[ink(message)]
+        fn renounce_ownership(&mut self) -> Result<()>;
+    }
+
+    /// Role-based access control, the owner grants and revokes the roles.
+    #[ink::trait_definition]
+    pub trait AccessControl {
+        /// Whether `account` has been granted `role`.
+        #[ink(message)]
+        fn has_role(&self, role: RoleType, account: AccountId) -> bool;
+
+        /// Grants `role` to `account`.
+        #[ink(message)]
+        fn grant_role(&mut self, role: RoleType, account: AccountId) -> Result<()>;
+
+        /// Revokes `role` from `account`.
+        #[ink(message)]
+        fn revoke_role(&mut self, role: RoleType, account: AccountId) -> Result<()>;
+    }
+
     #[ink(storage)]
     pub struct Erc20 {
         /// Total token supply.
@@ -87,9 +307,13 @@ mod erc20 {
         pause: bool,
         /// The contract owner, provides basic authorization control
         /// functions, this simplifies the implementation of "user permissions".
-        owner: AccountId,
+        owner: Option<AccountId>,
+        /// The account proposed as the next owner, until it accepts the ownership.
+        pending_owner: Option<AccountId>,
 
         blacklisted: StorageHashMap<AccountId, bool>,
+        /// The roles granted to each account.
+        roles: StorageHashMap<(RoleType, AccountId), ()>,
     }
 
     /// Event emitted when a token transfer occurs.
@@ -157,6 +381,45 @@ mod erc20 {
         amount: Balance,
     }
 
+    /// Event emitted when the owner proposes `new_owner` as the next owner.
+    #[ink(event)]
+    pub struct OwnershipTransferStarted {
+        #[ink(topic)]
+        previous_owner: Option<AccountId>,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
+    /// Event emitted when the ownership moves to `new_owner`, `None` when renounced.
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_owner: Option<AccountId>,
+        #[ink(topic)]
+        new_owner: Option<AccountId>,
+    }
+
+    /// Event emitted when `sender` grants `role` to `account`.
+    #[ink(event)]
+    pub struct RoleGranted {
+        #[ink(topic)]
+        role: RoleType,
+        #[ink(topic)]
+        account: AccountId,
+        sender: AccountId,
+    }
+
+    /// Event emitted when `sender` revokes `role` from `account`.
+    #[ink(event)]
+    pub struct RoleRevoked {
+        #[ink(topic)]
+        role: RoleType,
+        #[ink(topic)]
+        account: AccountId,
+        sender: AccountId,
+    }
+
+    #[cfg(feature = "ierc20")]
     impl IErc20 for Erc20 {
         #[ink(constructor)]
         fn new(
@@ -165,26 +428,7 @@ mod erc20 {
             symbol: Option<String>,
             decimals: Option<u8>,
         ) -> Self {
-            let caller = Self::env().caller();
-            let mut balances = StorageHashMap::new();
-            balances.insert(caller, initial_supply);
-            let instance = Self {
-                total_supply: Lazy::new(initial_supply),
-                balances,
-                allowances: StorageHashMap::new(),
-                name,
-                symbol,
-                decimals,
-                pause: false,
-                owner: caller,
-                blacklisted: Default::default(),
-            };
-            Self::env().emit_event(Transfer {
-                from: None,
-                to: Some(caller),
-                value: initial_supply,
-            });
-            instance
+            Self::new_init(initial_supply, name, symbol, decimals)
         }
 
         /// Returns the token name.
@@ -216,7 +460,7 @@ mod erc20 {
         /// Returns `0` if the account is non-existent.
         #[ink(message)]
         fn balance_of(&self, owner: AccountId) -> Balance {
-            self.balances.get(&owner).copied().unwrap_or(0)
+            self.get_balance(owner)
         }
 
         /// Transfers `value` amount of tokens from the caller's account to account `to`.
@@ -227,8 +471,12 @@ mod erc20 {
         ///
         /// Returns `InsufficientBalance` error if there are not enough tokens on
         /// the caller's account balance.
+        ///
+        /// Returns `Paused` error if the token is paused.
+        ///
+        /// Returns `BlacklistedUser` error if the caller or `to` is blacklisted.
         #[ink(message)]
-        fn transfer(&mut self, to: AccountId, value: Balance) -> IResult<()> {
+        fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
             let from = self.env().caller();
             self.transfer_from_to(from, to, value)
         }
@@ -238,7 +486,7 @@ mod erc20 {
         /// Returns `0` if no allowance has been set `0`.
         #[ink(message)]
         fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
-            self.allowances.get(&(owner, spender)).copied().unwrap_or(0)
+            self.get_allowance(owner, spender)
         }
 
         /// Transfers `value` tokens on the behalf of `from` to the account `to`.
@@ -255,16 +503,115 @@ mod erc20 {
         ///
         /// Returns `InsufficientBalance` error if there are not enough tokens on
         /// the the account balance of `from`.
+        ///
+        /// Returns `Paused` error if the token is paused.
+        ///
+        /// Returns `BlacklistedUser` error if the caller, `from` or `to` is blacklisted.
         #[ink(message)]
-        fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance) -> IResult<()> {
-            let caller = self.env().caller();
-            let allowance = self.allowance(from, caller);
-            if allowance < value {
-                return Err(IError::InsufficientAllowance);
+        fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<()> {
+            self.spend_allowance_and_transfer(from, to, value)
+        }
+
+        /// Allows `spender` to withdraw from the caller's account multiple times, up to
+        /// the `value` amount.
+        ///
+        /// If this function is called again it overwrites the current allowance with `value`.
+        ///
+        /// On success an `Approval` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Paused` error if the token is paused.
+        ///
+        /// Returns `BlacklistedUser` error if the caller or `spender` is blacklisted.
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
+            let owner = self.env().caller();
+            self.approve_from_to(owner, spender, value)
+        }
+    }
+
+    impl PSP22 for Erc20 {
+        /// Returns the total token supply.
+        #[ink(message)]
+        fn total_supply(&self) -> Balance {
+            *self.total_supply
+        }
+
+        /// Returns the account balance for the specified `owner`.
+        ///
+        /// Returns `0` if the account is non-existent.
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance {
+            self.get_balance(owner)
+        }
+
+        /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+        ///
+        /// Returns `0` if no allowance has been set.
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.get_allowance(owner, spender)
+        }
+
+        /// Transfers `value` amount of tokens from the caller's account to account `to`.
+        ///
+        /// `data` is not used by the token.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ZeroRecipientAddress` error if `to` is the zero account.
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the caller's account balance.
+        ///
+        /// Returns `Custom("Paused")` error if the token is paused, and
+        /// `Custom("BlacklistedUser")` if the caller or `to` is blacklisted.
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: Balance, _data: Vec<u8>) -> PSP22Result<()> {
+            if to == AccountId::default() {
+                return Err(PSP22Error::ZeroRecipientAddress);
             }
-            self.transfer_from_to(from, to, value)?;
-            self.allowances.insert((from, caller), allowance - value);
-            Ok(())
+            let from = self.env().caller();
+            Ok(self.transfer_from_to(from, to, value)?)
+        }
+
+        /// Transfers `value` tokens on the behalf of `from` to the account `to`.
+        ///
+        /// `data` is not used by the token.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ZeroSenderAddress` or `ZeroRecipientAddress` error if `from` or `to`
+        /// is the zero account.
+        ///
+        /// Returns `InsufficientAllowance` error if there are not enough tokens allowed
+        /// for the caller to withdraw from `from`.
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the account balance of `from`.
+        ///
+        /// Returns `Custom("Paused")` error if the token is paused, and
+        /// `Custom("BlacklistedUser")` if the caller, `from` or `to` is blacklisted.
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> PSP22Result<()> {
+            if from == AccountId::default() {
+                return Err(PSP22Error::ZeroSenderAddress);
+            }
+            if to == AccountId::default() {
+                return Err(PSP22Error::ZeroRecipientAddress);
+            }
+            Ok(self.spend_allowance_and_transfer(from, to, value)?)
         }
 
         /// Allows `spender` to withdraw from the caller's account multiple times, up to
@@ -272,47 +619,232 @@ mod erc20 {
         ///
         /// If this function is called again it overwrites the current allowance with `value`.
         ///
-        /// An `Approval` event is emitted.
+        /// On success an `Approval` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ZeroRecipientAddress` error if `spender` is the zero account.
+        ///
+        /// Returns `Custom("Paused")` error if the token is paused, and
+        /// `Custom("BlacklistedUser")` if the caller or `spender` is blacklisted.
         #[ink(message)]
-        fn approve(&mut self, spender: AccountId, value: Balance) -> IResult<()> {
+        fn approve(&mut self, spender: AccountId, value: Balance) -> PSP22Result<()> {
+            if spender == AccountId::default() {
+                return Err(PSP22Error::ZeroRecipientAddress);
+            }
             let owner = self.env().caller();
-            self.allowances.insert((owner, spender), value);
-            self.env().emit_event(Approval {
-                owner,
-                spender,
-                value,
+            Ok(self.approve_from_to(owner, spender, value)?)
+        }
+
+        /// Raises the allowance granted to `spender` by the caller by `delta_value`,
+        /// saturating at the maximum balance.
+        ///
+        /// On success an `Approval` event is emitted with the new allowance.
+        ///
+        /// # Errors
+        ///
+        /// Same as `approve`.
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> PSP22Result<()> {
+            if spender == AccountId::default() {
+                return Err(PSP22Error::ZeroRecipientAddress);
+            }
+            let owner = self.env().caller();
+            let allowance = self.get_allowance(owner, spender);
+            Ok(self.approve_from_to(owner, spender, allowance.saturating_add(delta_value))?)
+        }
+
+        /// Lowers the allowance granted to `spender` by the caller by `delta_value`.
+        ///
+        /// On success an `Approval` event is emitted with the new allowance.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientAllowance` error if `delta_value` is above the current
+        /// allowance.
+        ///
+        /// Otherwise same as `approve`.
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> PSP22Result<()> {
+            if spender == AccountId::default() {
+                return Err(PSP22Error::ZeroRecipientAddress);
+            }
+            let owner = self.env().caller();
+            let allowance = self.get_allowance(owner, spender);
+            if allowance < delta_value {
+                return Err(PSP22Error::InsufficientAllowance);
+            }
+            Ok(self.approve_from_to(owner, spender, allowance - delta_value)?)
+        }
+    }
+
+    impl PSP22Metadata for Erc20 {
+        /// Returns the token name.
+        #[ink(message)]
+        fn token_name(&self) -> Option<String> {
+            self.name.clone()
+        }
+
+        /// Returns the token symbol.
+        #[ink(message)]
+        fn token_symbol(&self) -> Option<String> {
+            self.symbol.clone()
+        }
+
+        /// Returns the token decimals, `0` if none were given at creation.
+        #[ink(message)]
+        fn token_decimals(&self) -> u8 {
+            self.decimals.unwrap_or(0)
+        }
+    }
+
+    impl Ownable for Erc20 {
+        /// Contract owner, `None` once the ownership is renounced.
+        #[ink(message)]
+        fn owner(&self) -> Option<AccountId> {
+            self.owner
+        }
+
+        /// Account proposed as the next owner, if any.
+        #[ink(message)]
+        fn pending_owner(&self) -> Option<AccountId> {
+            self.pending_owner
+        }
+
+        /// Proposes `new_owner` as the next owner, replacing any previous proposal.
+        ///
+        /// The ownership only moves once `new_owner` calls `accept_ownership`.
+        ///
+        /// On success an `OwnershipTransferStarted` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `OnlyOwnerAccess` error if the caller is not the owner.
+        ///
+        /// Returns `InvalidNewOwner` error if `new_owner` is the zero account.
+        #[ink(message)]
+        fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<()> {
+            self.only_owner()?;
+            if new_owner == AccountId::default() {
+                return Err(Error::InvalidNewOwner);
+            }
+            self.pending_owner = Some(new_owner);
+            self.env().emit_event(OwnershipTransferStarted {
+                previous_owner: self.owner,
+                new_owner,
+            });
+            Ok(())
+        }
+
+        /// Makes the caller, the pending owner, the owner.
+        ///
+        /// On success an `OwnershipTransferred` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InvalidNewOwner` error if the caller is not the pending owner.
+        #[ink(message)]
+        fn accept_ownership(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            if self.pending_owner != Some(caller) {
+                return Err(Error::InvalidNewOwner);
+            }
+            let previous_owner = self.owner;
+            self.owner = Some(caller);
+            self.pending_owner = None;
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner: Some(caller),
+            });
+            Ok(())
+        }
+
+        /// Leaves the contract without owner, and drops any pending proposal.
+        ///
+        /// Roles can no longer be granted nor revoked afterwards.
+        ///
+        /// On success an `OwnershipTransferred` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `OnlyOwnerAccess` error if the caller is not the owner.
+        #[ink(message)]
+        fn renounce_ownership(&mut self) -> Result<()> {
+            self.only_owner()?;
+            let previous_owner = self.owner;
+            self.owner = None;
+            self.pending_owner = None;
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner: None,
             });
             Ok(())
         }
     }
 
-    impl Ownable for Erc20 {
-        #[ink(constructor)]
-        fn new() -> Self {
-            unimplemented!()
+    impl AccessControl for Erc20 {
+        /// Whether `account` has been granted `role`.
+        #[ink(message)]
+        fn has_role(&self, role: RoleType, account: AccountId) -> bool {
+            self.roles.contains_key(&(role, account))
         }
 
-        /// Contract owner.
+        /// Grants `role` to `account`.
+        ///
+        /// A `RoleGranted` event is emitted if `account` didn't have the role yet.
+        ///
+        /// # Errors
+        ///
+        /// Returns `OnlyOwnerAccess` error if the caller is not the owner.
         #[ink(message)]
-        fn owner(&self) -> Option<AccountId> {
-            Some(self.owner)
+        fn grant_role(&mut self, role: RoleType, account: AccountId) -> Result<()> {
+            self.only_owner()?;
+            if self.roles.insert((role, account), ()).is_none() {
+                self.env().emit_event(RoleGranted {
+                    role,
+                    account,
+                    sender: self.env().caller(),
+                });
+            }
+            Ok(())
         }
 
-        /// transfer contract ownership to new owner.
+        /// Revokes `role` from `account`.
+        ///
+        /// A `RoleRevoked` event is emitted if `account` had the role.
+        ///
+        /// # Errors
+        ///
+        /// Returns `OnlyOwnerAccess` error if the caller is not the owner.
         #[ink(message)]
-        fn transfer_ownership(&mut self, new_owner: Option<AccountId>) {
-            self.only_owner();
-            if let Some(owner) = new_owner {
-                self.owner = owner;
+        fn revoke_role(&mut self, role: RoleType, account: AccountId) -> Result<()> {
+            self.only_owner()?;
+            if self.roles.take(&(role, account)).is_some() {
+                self.env().emit_event(RoleRevoked {
+                    role,
+                    account,
+                    sender: self.env().caller(),
+                });
             }
+            Ok(())
         }
     }
 
     impl Pausable for Erc20 {
         /// Pause contract transaction.
+        ///
+        /// Returns `MissingRole` error if the caller is not a `PAUSER`.
         #[ink(message)]
         fn pause(&mut self) -> Result<()> {
-            self.only_owner();
+            self.only_role(PAUSER)?;
 
             if !self.pause {
                 self.pause = true;
@@ -322,9 +854,11 @@ mod erc20 {
         }
 
         /// Recover paused contract.
+        ///
+        /// Returns `MissingRole` error if the caller is not a `PAUSER`.
         #[ink(message)]
         fn unpause(&mut self) -> Result<()> {
-            self.only_owner();
+            self.only_role(PAUSER)?;
             if self.pause {
                 self.pause = false;
                 self.env().emit_event(Unpause {})
@@ -347,29 +881,38 @@ mod erc20 {
         }
 
         /// Add illegal user to blacklist.
+        ///
+        /// Returns `MissingRole` error if the caller is not a `BLACKLISTER`.
         #[ink(message)]
         fn add_blacklist(&mut self, evil_user: AccountId) -> Result<()> {
-            self.only_owner();
+            self.only_role(BLACKLISTER)?;
             self.blacklisted.insert(evil_user, true);
+            self.env().emit_event(AddedBlackList { user: evil_user });
             Ok(())
         }
 
         /// Remove the user from blacklist.
+        ///
+        /// Returns `MissingRole` error if the caller is not a `BLACKLISTER`.
         #[ink(message)]
         fn remove_blacklist(&mut self, cleared_user: AccountId) -> Result<()> {
-            self.only_owner();
+            self.only_role(BLACKLISTER)?;
             self.blacklisted.take(&cleared_user);
+            self.env()
+                .emit_event(RemovedBlackList { user: cleared_user });
             Ok(())
         }
 
         /// Destroy blacklisted user funds from total supply.
+        ///
+        /// Returns `MissingRole` error if the caller is not a `BLACKLISTER`.
         #[ink(message)]
         fn destroy_blackfunds(&mut self, blacklisted_user: AccountId) -> Result<()> {
-            self.only_owner();
+            self.only_role(BLACKLISTER)?;
             if !self.get_blacklist_status(blacklisted_user) {
                 return Err(Error::NotBlacklistedUser);
             }
-            let dirty_funds = self.balance_of(blacklisted_user);
+            let dirty_funds = self.get_balance(blacklisted_user);
             self.balances.insert(blacklisted_user, 0);
             *self.total_supply -= dirty_funds;
             self.env().emit_event(DestroyedBlackFunds {
@@ -381,17 +924,38 @@ mod erc20 {
     }
 
     impl Erc20 {
+        /// Creates a new token with the specified initial supply, owned by the caller.
+        ///
+        /// The caller is granted all the roles.
+        #[ink(constructor)]
+        pub fn new(
+            initial_supply: Balance,
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: Option<u8>,
+        ) -> Self {
+            Self::new_init(initial_supply, name, symbol, decimals)
+        }
+
         /// Mint a new amount of tokens
         /// these tokens are deposited into the owner address
+        ///
+        /// Fails with `MissingRole` if the caller is not a `MINTER`, with `ZeroAddress` if
+        /// `user` is the zero account, with `Paused` while the token is paused and with
+        /// `BlacklistedUser` if `user` is blacklisted.
         #[ink(message)]
         pub fn mint(&mut self, user: AccountId, amount: Balance) -> Result<()> {
-            self.only_owner();
-            assert_ne!(user, Default::default());
+            self.only_role(MINTER)?;
+            if user == AccountId::default() {
+                return Err(Error::ZeroAddress);
+            }
             if amount <= 0 {
                 return Err(Error::InvalidAmount);
             }
+            self.when_not_paused()?;
+            self.not_blacklisted(user)?;
 
-            let user_balance = self.balance_of(user);
+            let user_balance = self.get_balance(user);
             self.balances.insert(user, user_balance + amount);
             *self.total_supply += amount;
             self.env().emit_event(Mint { user, amount });
@@ -402,13 +966,18 @@ mod erc20 {
         /// These tokens are withdrawn from the owner address
         /// if the balance must be enough to cover the redeem
         /// or the call will fail.
+        ///
+        /// Fails with `MissingRole` if the caller is not a `BURNER` and with `Paused`
+        /// while the token is paused, blacklisted funds are removed with
+        /// `destroy_blackfunds` instead.
         #[ink(message)]
         pub fn burn(&mut self, user: AccountId, amount: Balance) -> Result<()> {
-            self.only_owner();
+            self.only_role(BURNER)?;
+            self.when_not_paused()?;
             if *self.total_supply < amount {
                 return Err(Error::InsufficientSupply);
             }
-            let user_balance = self.balance_of(user);
+            let user_balance = self.get_balance(user);
             if user_balance < amount {
                 return Err(Error::InsufficientBalance);
             }
@@ -427,18 +996,25 @@ mod erc20 {
         ///
         /// Returns `InsufficientBalance` error if there are not enough tokens on
         /// the caller's account balance.
+        ///
+        /// Returns `Paused` error if the token is paused.
+        ///
+        /// Returns `BlacklistedUser` error if `from` or `to` is blacklisted.
         fn transfer_from_to(
             &mut self,
             from: AccountId,
             to: AccountId,
             value: Balance,
-        ) -> IResult<()> {
-            let from_balance = self.balance_of(from);
+        ) -> Result<()> {
+            self.when_not_paused()?;
+            self.not_blacklisted(from)?;
+            self.not_blacklisted(to)?;
+            let from_balance = self.get_balance(from);
             if from_balance < value {
-                return Err(IError::InsufficientBalance);
+                return Err(Error::InsufficientBalance);
             }
             self.balances.insert(from, from_balance - value);
-            let to_balance = self.balance_of(to);
+            let to_balance = self.get_balance(to);
             self.balances.insert(to, to_balance + value);
             self.env().emit_event(Transfer {
                 from: Some(from),
@@ -448,8 +1024,118 @@ mod erc20 {
             Ok(())
         }
 
-        fn only_owner(&self) {
-            assert_eq!(self.env().caller(), self.owner);
+        fn new_init(
+            initial_supply: Balance,
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: Option<u8>,
+        ) -> Self {
+            let caller = Self::env().caller();
+            let mut balances = StorageHashMap::new();
+            balances.insert(caller, initial_supply);
+            let mut roles = StorageHashMap::new();
+            for role in &[MINTER, BURNER, PAUSER, BLACKLISTER] {
+                roles.insert((*role, caller), ());
+            }
+            let instance = Self {
+                total_supply: Lazy::new(initial_supply),
+                balances,
+                allowances: StorageHashMap::new(),
+                name,
+                symbol,
+                decimals,
+                pause: false,
+                owner: Some(caller),
+                pending_owner: None,
+                blacklisted: Default::default(),
+                roles,
+            };
+            Self::env().emit_event(Transfer {
+                from: None,
+                to: Some(caller),
+                value: initial_supply,
+            });
+            Self::env().emit_event(OwnershipTransferred {
+                previous_owner: None,
+                new_owner: Some(caller),
+            });
+            instance
+        }
+
+        fn get_balance(&self, owner: AccountId) -> Balance {
+            self.balances.get(&owner).copied().unwrap_or(0)
+        }
+
+        fn get_allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get(&(owner, spender)).copied().unwrap_or(0)
+        }
+
+        /// Transfers `value` tokens from `from` to `to` out of the allowance of the caller.
+        fn spend_allowance_and_transfer(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            self.when_not_paused()?;
+            self.not_blacklisted(caller)?;
+            let allowance = self.get_allowance(from, caller);
+            if allowance < value {
+                return Err(Error::InsufficientAllowance);
+            }
+            self.transfer_from_to(from, to, value)?;
+            self.allowances.insert((from, caller), allowance - value);
+            Ok(())
+        }
+
+        /// Sets the allowance of `spender` over the tokens of `owner` to `value`.
+        ///
+        /// On success an `Approval` event is emitted.
+        fn approve_from_to(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            self.when_not_paused()?;
+            self.not_blacklisted(owner)?;
+            self.not_blacklisted(spender)?;
+            self.allowances.insert((owner, spender), value);
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value,
+            });
+            Ok(())
+        }
+
+        fn only_owner(&self) -> Result<()> {
+            if self.owner != Some(self.env().caller()) {
+                return Err(Error::OnlyOwnerAccess);
+            }
+            Ok(())
+        }
+
+        fn only_role(&self, role: RoleType) -> Result<()> {
+            if !self.has_role(role, self.env().caller()) {
+                return Err(Error::MissingRole);
+            }
+            Ok(())
+        }
+
+        fn when_not_paused(&self) -> Result<()> {
+            if self.pause {
+                return Err(Error::Paused);
+            }
+            Ok(())
+        }
+
+        fn not_blacklisted(&self, user: AccountId) -> Result<()> {
+            if self.get_blacklist_status(user) {
+                return Err(Error::BlacklistedUser);
+            }
+            Ok(())
         }
     }
 }
@@ -0,0 +1,123 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::collector::Collector;
+use ink_lang as ink;
+
+/// Treasury of the protocol.
+///
+/// The lending pool mints the reserve factor share of the borrow interest to it as sTokens
+/// through `mint_to_treasury`, which only its admin can move out.
+#[ink::contract]
+mod collector {
+    use ink_env::call::FromAccountId;
+    use stoken::Stoken;
+
+    /// The collector error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller is not the collector admin.
+        OnlyAdminAccess,
+        /// Returned if the token rejects the transfer.
+        TransferFailed,
+    }
+
+    /// The collector result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Event emitted when the admin moves funds out of the collector.
+    #[ink(event)]
+    pub struct FundsTransferred {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        recipient: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when the withdrawal rights move to another account.
+    #[ink(event)]
+    pub struct AdminTransferred {
+        #[ink(topic)]
+        previous_admin: AccountId,
+        #[ink(topic)]
+        new_admin: AccountId,
+    }
+
+    #[ink(storage)]
+    pub struct Collector {
+        /// The account allowed to move the funds.
+        admin: AccountId,
+    }
+
+    impl Collector {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                admin: Self::env().caller(),
+            }
+        }
+
+        /// Transfers `amount` of the sToken `token` held by the collector to `recipient`.
+        ///
+        /// On success a `FundsTransferred` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `OnlyAdminAccess` error if the caller is not the admin.
+        ///
+        /// Returns `TransferFailed` error if the token rejects the transfer, e.g. as the
+        /// collector holds less than `amount`.
+        #[ink(message)]
+        pub fn transfer(
+            &mut self,
+            token: AccountId,
+            recipient: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            self.only_admin()?;
+            let mut stoken: Stoken = FromAccountId::from_account_id(token);
+            stoken
+                .transfer(recipient, amount)
+                .map_err(|_| Error::TransferFailed)?;
+            self.env().emit_event(FundsTransferred {
+                token,
+                recipient,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Returns the account allowed to move the funds.
+        #[ink(message)]
+        pub fn admin(&self) -> AccountId {
+            self.admin
+        }
+
+        /// Hands the withdrawal rights to `new_admin`.
+        ///
+        /// On success an `AdminTransferred` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `OnlyAdminAccess` error if the caller is not the admin.
+        #[ink(message)]
+        pub fn transfer_admin(&mut self, new_admin: AccountId) -> Result<()> {
+            self.only_admin()?;
+            let previous_admin = self.admin;
+            self.admin = new_admin;
+            self.env().emit_event(AdminTransferred {
+                previous_admin,
+                new_admin,
+            });
+            Ok(())
+        }
+
+        fn only_admin(&self) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::OnlyAdminAccess);
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use crate::types::{LendingError, Result};
+use ink_storage::traits::{PackedLayout, SpreadLayout};
+use raymath::*;
+
+/// Computes the rates of a reserve from the state of its liquidity.
+///
+/// Each reserve stores its own `DefaultInterestRateStrategy`, which governance can replace
+/// with `set_reserve_interest_rate_strategy`. Another model has to implement this trait and
+/// take its place in `ReserveData`.
+pub trait InterestRateStrategy {
+    /// Returns the yearly `(liquidity_rate, stable_borrow_rate, variable_borrow_rate)` of the
+    /// reserve, all in ray.
+    ///
+    /// `average_stable_borrow_rate` is the rate paid on `total_stable_debt`, in ray, and
+    /// `reserve_factor` is the share of the borrow interest kept by the protocol, in basis points.
+    fn calculate_interest_rates(
+        &self,
+        available_liquidity: u128,
+        total_stable_debt: u128,
+        total_variable_debt: u128,
+        average_stable_borrow_rate: u128,
+        reserve_factor: u128,
+    ) -> Result<(u128, u128, u128)>;
+}
+
+/// Two slopes model: the borrow rates grow gently with the utilisation up to
+/// `optimal_utilization_rate`, then steeply to push the utilisation back down.
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Copy,
+    Clone,
+    scale::Encode,
+    scale::Decode,
+    SpreadLayout,
+    PackedLayout,
+)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+)]
+pub struct DefaultInterestRateStrategy {
+    // utilisation at which the second slope starts, in ray.
+    pub optimal_utilization_rate: u128,
+    // variable borrow rate at zero utilisation, in ray.
+    pub base_borrow_rate: u128,
+    // variable borrow rate increase from zero to the optimal utilisation, in ray.
+    pub rate_slope1: u128,
+    // variable borrow rate increase from the optimal to full utilisation, in ray.
+    pub rate_slope2: u128,
+    // stable borrow rate at zero utilisation, in ray.
+    pub base_stable_borrow_rate: u128,
+    // stable borrow rate increase from zero to the optimal utilisation, in ray.
+    pub stable_rate_slope1: u128,
+    // stable borrow rate increase from the optimal to full utilisation, in ray.
+    pub stable_rate_slope2: u128,
+}
+
+impl DefaultInterestRateStrategy {
+    /// Checks that the optimal utilisation is in (0, `RAY`] and that the rates at full
+    /// utilisation fit in a `u128`, the slopes being unsigned they can't be negative.
+    pub fn validate(&self) -> Result<()> {
+        let max_rate = |base_rate: u128, slope1: u128, slope2: u128| {
+            base_rate
+                .checked_add(slope1)
+                .and_then(|rate| rate.checked_add(slope2))
+        };
+        if self.optimal_utilization_rate == 0
+            || self.optimal_utilization_rate > RAY
+            || max_rate(self.base_borrow_rate, self.rate_slope1, self.rate_slope2).is_none()
+            || max_rate(
+                self.base_stable_borrow_rate,
+                self.stable_rate_slope1,
+                self.stable_rate_slope2,
+            )
+            .is_none()
+        {
+            return Err(LendingError::RcInvalidInterestRateStrategy);
+        }
+        Ok(())
+    }
+
+    /// Rate growing by `slope1` from `base_rate` up to the optimal utilisation, then by `slope2`.
+    fn rate_at(
+        &self,
+        utilization_rate: u128,
+        base_rate: u128,
+        slope1: u128,
+        slope2: u128,
+    ) -> Result<u128> {
+        if utilization_rate > self.optimal_utilization_rate {
+            let excess_utilization_rate = ray_div(
+                utilization_rate - self.optimal_utilization_rate,
+                RAY.saturating_sub(self.optimal_utilization_rate),
+            )?;
+            Ok(base_rate
+                .saturating_add(slope1)
+                .saturating_add(ray_mul(slope2, excess_utilization_rate)?))
+        } else {
+            let slope = ray_div(utilization_rate, self.optimal_utilization_rate)?;
+            Ok(base_rate.saturating_add(ray_mul(slope1, slope)?))
+        }
+    }
+}
+
+impl InterestRateStrategy for DefaultInterestRateStrategy {
+    fn calculate_interest_rates(
+        &self,
+        available_liquidity: u128,
+        total_stable_debt: u128,
+        total_variable_debt: u128,
+        average_stable_borrow_rate: u128,
+        reserve_factor: u128,
+    ) -> Result<(u128, u128, u128)> {
+        let total_debt = total_stable_debt.saturating_add(total_variable_debt);
+        if total_debt == 0 {
+            return Ok((0, self.base_stable_borrow_rate, self.base_borrow_rate));
+        }
+        let total_liquidity = available_liquidity.saturating_add(total_debt);
+        let utilization_rate = ray_div(total_debt, total_liquidity)?;
+
+        let stable_borrow_rate = self.rate_at(
+            utilization_rate,
+            self.base_stable_borrow_rate,
+            self.stable_rate_slope1,
+            self.stable_rate_slope2,
+        )?;
+        let variable_borrow_rate = self.rate_at(
+            utilization_rate,
+            self.base_borrow_rate,
+            self.rate_slope1,
+            self.rate_slope2,
+        )?;
+
+        // rate paid on the whole debt, each part weighted by its size
+        let overall_borrow_rate = ray_div(
+            ray_mul(total_stable_debt, average_stable_borrow_rate)?
+                .saturating_add(ray_mul(total_variable_debt, variable_borrow_rate)?),
+            total_debt,
+        )?;
+        let liquidity_rate = percent_mul(
+            ray_mul(overall_borrow_rate, utilization_rate)?,
+            PERCENTAGE_FACTOR.saturating_sub(reserve_factor),
+        )?;
+        Ok((liquidity_rate, stable_borrow_rate, variable_borrow_rate))
+    }
+}
@@ -0,0 +1,218 @@
+/// Errors returned by the lending pool messages.
+///
+/// The discriminants are the numeric codes of the original protocol error table,
+/// so `LendingError::VlInvalidAmount as u8` is still `1`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum LendingError {
+    /// Amount must be greater than 0
+    VlInvalidAmount = 1,
+    /// Action requires an active reserve
+    VlNoActiveReserve = 2,
+    /// Action cannot be performed because the reserve is frozen
+    VlReserveFrozen = 3,
+    /// The current liquidity is not enough
+    VlCurrentAvailableLiquidityNotEnough = 4,
+    /// User cannot withdraw more than the available balance
+    VlNotEnoughAvailableUserBalance = 5,
+    /// Transfer cannot be allowed.
+    VlTransferNotAllowed = 6,
+    /// Borrowing is not enabled
+    VlBorrowingNotEnabled = 7,
+    /// Invalid interest rate mode selected
+    VlInvalidInterestRateModeSelected = 8,
+    /// The collateral balance is 0
+    VlCollateralBalanceIs0 = 9,
+    /// Health factor is lesser than the liquidation threshold
+    VlHealthFactorLowerThanLiquidationThreshold = 10,
+    /// There is not enough collateral to cover a new borrow
+    VlCollateralCannotCoverNewBorrow = 11,
+    /// stable borrowing not enabled
+    VlStableBorrowingNotEnabled = 12,
+    /// collateral is (mostly) the same currency that is being borrowed
+    VlCollateralSameAsBorrowingCurrency = 13,
+    /// The requested amount is greater than the max loan size in stable rate mode
+    VlAmountBiggerThanMaxLoanSizeStable = 14,
+    /// for repayment of stable debt, the user needs to have stable debt, otherwise, he needs to have variable debt
+    VlNoDebtOfSelectedType = 15,
+    /// To repay on behalf of an user an explicit amount to repay is needed
+    VlNoExplicitAmountToRepayOnBehalf = 16,
+    /// User does not have a stable rate loan in progress on this reserve
+    VlNoStableRateLoanInReserve = 17,
+    /// User does not have a variable rate loan in progress on this reserve
+    VlNoVariableRateLoanInReserve = 18,
+    /// The underlying balance needs to be greater than 0
+    VlUnderlyingBalanceNotGreaterThan0 = 19,
+    /// User deposit is already being used as collateral
+    VlDepositAlreadyInUse = 20,
+    /// User does not have any stable rate loan for this reserve
+    LpNotEnoughStableBorrowBalance = 21,
+    /// Interest rate rebalance conditions were not met
+    LpInterestRateRebalanceConditionsNotMet = 22,
+    /// Liquidation call failed
+    LpLiquidationCallFailed = 23,
+    /// There is not enough liquidity available to borrow
+    LpNotEnoughLiquidityToBorrow = 24,
+    /// The requested amount is too small for a FlashLoan.
+    LpRequestedAmountTooSmall = 25,
+    /// The actual balance of the protocol is inconsistent
+    LpInconsistentProtocolActualBalance = 26,
+    /// The caller of the function is not the lending pool configurator
+    LpCallerNotLendingPoolConfigurator = 27,
+    LpInconsistentFlashloanParams = 28,
+    /// The caller of this function must be a lending pool
+    CtCallerMustBeLendingPool = 29,
+    /// User cannot give allowance to himself
+    CtCannotGiveAllowanceToHimself = 30,
+    /// Transferred amount needs to be greater than zero
+    CtTransferAmountNotGt0 = 31,
+    /// Reserve has already been initialized
+    RlReserveAlreadyInitialized = 32,
+    /// The liquidity of the reserve needs to be 0
+    LpcReserveLiquidityNot0 = 34,
+    /// The liquidity of the reserve needs to be 0
+    LpcInvalidAtokenPoolAddress = 35,
+    /// The liquidity of the reserve needs to be 0
+    LpcInvalidStableDebtTokenPoolAddress = 36,
+    /// The liquidity of the reserve needs to be 0
+    LpcInvalidVariableDebtTokenPoolAddress = 37,
+    /// The liquidity of the reserve needs to be 0
+    LpcInvalidStableDebtTokenUnderlyingAddress = 38,
+    /// The liquidity of the reserve needs to be 0
+    LpcInvalidVariableDebtTokenUnderlyingAddress = 39,
+    /// The liquidity of the reserve needs to be 0
+    LpcInvalidAddressesProviderId = 40,
+    /// Invalid risk parameters for the reserve
+    LpcInvalidConfiguration = 75,
+    /// The caller must be the emergency admin
+    LpcCallerNotEmergencyAdmin = 76,
+    /// Provider is not registered
+    LpaprProviderNotRegistered = 41,
+    /// Health factor is not below the threshold
+    LpcmHealthFactorNotBelowThreshold = 42,
+    /// The collateral chosen cannot be liquidated
+    LpcmCollateralCannotBeLiquidated = 43,
+    /// User did not borrow the specified currency
+    LpcmSpecifiedCurrencyNotBorrowedByUser = 44,
+    /// "There isnt enough liquidity available to liquidate"
+    LpcmNotEnoughLiquidityToLiquidate = 45,
+    /// No errors
+    LpcmNoErrors = 46,
+    /// Invalid flashloan mode selected
+    LpInvalidFlashloanMode = 47,
+    MathMultiplicationOverflow = 48,
+    MathAdditionOverflow = 49,
+    MathDivisionByZero = 50,
+    /// Liquidity index overflows uint128
+    RlLiquidityIndexOverflow = 51,
+    /// Variable borrow index overflows uint128
+    RlVariableBorrowIndexOverflow = 52,
+    /// Liquidity rate overflows uint128
+    RlLiquidityRateOverflow = 53,
+    /// Variable borrow rate overflows uint128
+    RlVariableBorrowRateOverflow = 54,
+    /// Stable borrow rate overflows uint128
+    RlStableBorrowRateOverflow = 55,
+    /// invalid amount to mint
+    CtInvalidMintAmount = 56,
+    LpFailedRepayWithCollateral = 57,
+    /// invalid amount to burn
+    CtInvalidBurnAmount = 58,
+    /// User borrows on behalf, but allowance are too small
+    CtBorrowAllowanceNotEnough = 59,
+    LpFailedCollateralSwap = 60,
+    LpInvalidEqualAssetsToSwap = 61,
+    LpReentrancyNotAllowed = 62,
+    LpCallerMustBeAnAtoken = 63,
+    /// Pool is paused
+    LpIsPaused = 64,
+    LpNoMoreReservesAllowed = 65,
+    LpInvalidFlashLoanExecutorReturn = 66,
+    RcInvalidLtv = 67,
+    RcInvalidLiqThreshold = 68,
+    RcInvalidLiqBonus = 69,
+    RcInvalidDecimals = 70,
+    RcInvalidReserveFactor = 71,
+    LpaprInvalidAddressesProviderId = 72,
+    VlInconsistentFlashloanParams = 73,
+    LpInconsistentParamsLength = 74,
+    UlInvalidIndex = 77,
+    LpNotContract = 78,
+    SdtStableDebtOverflow = 79,
+    SdtBurnExceedsBalance = 80,
+    /// Not enough balance on the token account
+    Erc20InsufficientBalance = 81,
+    /// Not enough total supply on the token
+    Erc20InsufficientSupply = 82,
+    /// Not enough allowance granted to the pool
+    Erc20InsufficientAllowance = 83,
+    /// Neither the price oracle nor the fallback oracle has a fresh price for the asset
+    LpAssetPriceUnavailable = 84,
+    /// The borrow cap is above the maximum valid cap
+    RcInvalidBorrowCap = 85,
+    /// The supply cap is above the maximum valid cap
+    RcInvalidSupplyCap = 86,
+    /// The borrow would take the debt of the reserve above its borrow cap
+    VlBorrowCapExceeded = 87,
+    /// The deposit would take the sToken supply of the reserve above its supply cap
+    VlSupplyCapExceeded = 88,
+    /// The pool or the user is blacklisted on the token
+    Erc20BlacklistedUser = 89,
+    /// The token is paused
+    Erc20Paused = 90,
+    /// The token rejected a call reserved to its owner or to a role
+    Erc20CallRejected = 91,
+    /// The optimal utilisation is not in (0, 1] or the borrow rates would overflow
+    RcInvalidInterestRateStrategy = 92,
+}
+
+/// The lending pool result type.
+pub type Result<T> = core::result::Result<T, LendingError>;
+
+impl From<raymath::MathError> for LendingError {
+    fn from(error: raymath::MathError) -> Self {
+        match error {
+            raymath::MathError::DivisionByZero => LendingError::MathDivisionByZero,
+            raymath::MathError::MultiplicationOverflow => LendingError::MathMultiplicationOverflow,
+            raymath::MathError::AdditionOverflow => LendingError::MathAdditionOverflow,
+        }
+    }
+}
+
+impl From<erc20::Error> for LendingError {
+    fn from(error: erc20::Error) -> Self {
+        match error {
+            erc20::Error::InsufficientBalance => LendingError::Erc20InsufficientBalance,
+            erc20::Error::InsufficientSupply => LendingError::Erc20InsufficientSupply,
+            erc20::Error::InsufficientAllowance => LendingError::Erc20InsufficientAllowance,
+            erc20::Error::BlacklistedUser => LendingError::Erc20BlacklistedUser,
+            erc20::Error::InvalidAmount => LendingError::VlInvalidAmount,
+            erc20::Error::Paused => LendingError::Erc20Paused,
+            erc20::Error::OnlyOwnerAccess
+            | erc20::Error::InvalidNewOwner
+            | erc20::Error::NotBlacklistedUser
+            | erc20::Error::MissingRole
+            | erc20::Error::ZeroAddress => LendingError::Erc20CallRejected,
+        }
+    }
+}
+
+impl From<erc20::PSP22Error> for LendingError {
+    fn from(error: erc20::PSP22Error) -> Self {
+        match error {
+            erc20::PSP22Error::InsufficientBalance => LendingError::Erc20InsufficientBalance,
+            erc20::PSP22Error::InsufficientAllowance => LendingError::Erc20InsufficientAllowance,
+            // the token reports its own errors by variant name.
+            erc20::PSP22Error::Custom(name) => match name.as_str() {
+                "InsufficientSupply" => LendingError::Erc20InsufficientSupply,
+                "BlacklistedUser" => LendingError::Erc20BlacklistedUser,
+                "InvalidAmount" => LendingError::VlInvalidAmount,
+                "Paused" => LendingError::Erc20Paused,
+                _ => LendingError::Erc20CallRejected,
+            },
+            erc20::PSP22Error::ZeroRecipientAddress
+            | erc20::PSP22Error::ZeroSenderAddress
+            | erc20::PSP22Error::SafeTransferCheckFailed(_) => LendingError::Erc20CallRejected,
+        }
+    }
+}
@@ -0,0 +1,123 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::errors::{LendingError, Result};
+pub use self::interfaces::{Callee, FlashLoanReceiver, PoolCallbacks};
+use ink_lang as ink;
+
+mod errors;
+
+/// Messages the lending pool and the contracts around it call on each other.
+///
+/// The pool depends on the tokens of its reserves, so they can't depend on the pool:
+/// both sides share these trait definitions instead, as do the flash loan receivers.
+#[ink::contract]
+mod interfaces {
+    use crate::errors::Result;
+    use ink_prelude::vec::Vec;
+
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_lang as ink;
+
+    /// Messages of the lending pool called back by the tokens of its reserves.
+    #[ink::trait_definition]
+    pub trait PoolCallbacks {
+        /// Returns the liquidity index of the `asset` reserve at the current block, in ray.
+        ///
+        /// Returns `0` if `asset` is not listed.
+        #[ink(message)]
+        fn get_reserve_normalized_income(&self, asset: AccountId) -> u128;
+
+        /// Returns the variable borrow index of the `asset` reserve at the current block, in ray.
+        ///
+        /// Returns `0` if `asset` is not listed.
+        #[ink(message)]
+        fn get_reserve_normalized_variable_debt(&self, asset: AccountId) -> u128;
+
+        /// Validates a transfer of `amount` sTokens of the `asset` reserve, before the
+        /// balances are moved.
+        #[ink(message)]
+        fn finalize_transfer(
+            &mut self,
+            asset: AccountId,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+            balance_from_before: Balance,
+            balance_to_before: Balance,
+        ) -> Result<()>;
+    }
+
+    /// Callback the pool runs on the receiver of a flash loan.
+    #[ink::trait_definition]
+    pub trait FlashLoanReceiver {
+        /// Executes an operation after receiving the flash-borrowed assets.
+        ///
+        /// For every asset opened without debt, the receiver must have approved the pool to
+        /// pull back `amount + premium` before returning.
+        ///
+        /// Returns `true` if the operation succeeded, anything else rolls the flash loan back.
+        #[ink(message)]
+        fn execute_operation(
+            &mut self,
+            assets: Vec<AccountId>,
+            amounts: Vec<Balance>,
+            premiums: Vec<Balance>,
+            initiator: AccountId,
+            params: Vec<u8>,
+        ) -> bool;
+    }
+
+    /// Handle on any contract implementing `PoolCallbacks` or `FlashLoanReceiver`, built
+    /// with `FromAccountId`.
+    ///
+    /// Its messages are the ones of the trait definitions, so their selectors and
+    /// signatures always match the implementations. It is never deployed.
+    #[ink(storage)]
+    pub struct Callee {}
+
+    impl Callee {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {}
+        }
+    }
+
+    impl PoolCallbacks for Callee {
+        #[ink(message)]
+        fn get_reserve_normalized_income(&self, _asset: AccountId) -> u128 {
+            unimplemented!()
+        }
+
+        #[ink(message)]
+        fn get_reserve_normalized_variable_debt(&self, _asset: AccountId) -> u128 {
+            unimplemented!()
+        }
+
+        #[ink(message)]
+        fn finalize_transfer(
+            &mut self,
+            _asset: AccountId,
+            _from: AccountId,
+            _to: AccountId,
+            _amount: Balance,
+            _balance_from_before: Balance,
+            _balance_to_before: Balance,
+        ) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    impl FlashLoanReceiver for Callee {
+        #[ink(message)]
+        fn execute_operation(
+            &mut self,
+            _assets: Vec<AccountId>,
+            _amounts: Vec<Balance>,
+            _premiums: Vec<Balance>,
+            _initiator: AccountId,
+            _params: Vec<u8>,
+        ) -> bool {
+            unimplemented!()
+        }
+    }
+}
@@ -1,17 +1,26 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+mod interest_rate;
+mod token;
 mod types;
 
 use ink_lang as ink;
 
 #[ink::contract]
 mod lendingpool {
+    use crate::interest_rate::*;
+    use crate::token::Token;
     use crate::types::*;
-    use ierc20::IERC20;
+    use interfaces::{Callee, FlashLoanReceiver, PoolCallbacks};
+    use oracle::PriceOracle;
+    use raymath::*;
+    use stabledebttoken::StableDebtToken;
+    use stoken::Stoken;
+    use variabledebttoken::VariableDebtToken;
 
     use ink_env::call::FromAccountId;
-    use ink_prelude::{vec, vec::Vec};
-    use ink_storage::collections::HashMap as StorageHashMap;
+    use ink_prelude::vec::Vec;
+    use ink_storage::collections::{HashMap as StorageHashMap, Vec as StorageVec};
 
     /// * @dev Emitted on deposit()
     /// * @param reserve The address of the underlying asset of the reserve
@@ -20,11 +29,12 @@ mod lendingpool {
     /// * @param amount The amount deposited
     #[ink(event)]
     pub struct Deposit {
+        #[ink(topic)]
+        reserve: AccountId,
         #[ink(topic)]
         user: AccountId,
         #[ink(topic)]
         on_behalf_of: AccountId,
-        #[ink(topic)]
         amount: Balance,
     }
 
@@ -35,225 +45,692 @@ mod lendingpool {
     /// * @param amount The amount to be withdrawn
     #[ink(event)]
     pub struct Withdraw {
+        #[ink(topic)]
+        reserve: AccountId,
         #[ink(topic)]
         user: AccountId,
         #[ink(topic)]
         to: AccountId,
-        #[ink(topic)]
         amount: Balance,
     }
 
     /**
      * @dev Emitted on borrow() when debt needs to be opened
+     * @param reserve The address of the underlying asset being borrowed
      * @param user The address of the user initiating the borrow(), receiving the funds on borrow()
      * @param onBehalfOf The address that will be getting the debt
      * @param amount The amount borrowed out
-     * @param borrowRate The numeric rate at which the user has borrowed
+     * @param borrow_rate_mode The rate mode: 1 for Stable, 2 for Variable
+     * @param borrow_rate The numeric rate at which the user has borrowed
      **/
     #[ink(event)]
     pub struct Borrow {
+        #[ink(topic)]
+        reserve: AccountId,
         #[ink(topic)]
         user: AccountId,
         #[ink(topic)]
         on_behalf_of: AccountId,
-        #[ink(topic)]
         amount: Balance,
+        borrow_rate_mode: InterestRateMode,
+        borrow_rate: u128,
     }
 
     /**
      * @dev Emitted on repay()
+     * @param reserve The address of the underlying asset of the reserve
      * @param receiver The beneficiary of the repayment, getting his debt reduced
      * @param repayer The address of the user initiating the repay(), providing the funds
      * @param amount The amount repaid
      **/
     #[ink(event)]
     pub struct Repay {
+        #[ink(topic)]
+        reserve: AccountId,
         #[ink(topic)]
         receiver: AccountId,
         #[ink(topic)]
         repayer: AccountId,
-        #[ink(topic)]
         amount: Balance,
     }
 
     /**
-     * @dev emitted on approvedelegation
-     * @param delegator  who have money and allow delegatee use it as collateral
-     * @param delegatee who can borrow money from pool without collateral
-     * @param amount the amount
+     * @dev Emitted on flash_loan()
+     * @param target The address of the flash loan receiver contract
+     * @param initiator The address initiating the flash loan
+     * @param asset The address of the asset being flash borrowed
+     * @param amount The amount flash borrowed
+     * @param premium The fee paid for the flash loan, zero when debt was opened instead
      **/
     #[ink(event)]
-    pub struct Delegate {
+    pub struct FlashLoan {
         #[ink(topic)]
-        delegator: AccountId,
+        target: AccountId,
         #[ink(topic)]
-        delegatee: AccountId,
+        initiator: AccountId,
         #[ink(topic)]
+        asset: AccountId,
         amount: Balance,
+        premium: Balance,
+    }
+
+    /**
+     * @dev Emitted on swap_borrow_rate_mode()
+     * @param reserve The address of the underlying asset of the reserve
+     * @param user The address of the user swapping his rate mode
+     * @param rate_mode The rate mode that the user wants to swap to
+     **/
+    #[ink(event)]
+    pub struct Swap {
+        #[ink(topic)]
+        reserve: AccountId,
+        #[ink(topic)]
+        user: AccountId,
+        rate_mode: InterestRateMode,
+    }
+
+    /**
+     * @dev Emitted on rebalance_stable_borrow_rate()
+     * @param reserve The address of the underlying asset of the reserve
+     * @param user The address of the user whose stable debt was rebalanced
+     * @param new_rate The stable rate of the user after the rebalance
+     **/
+    #[ink(event)]
+    pub struct RebalanceStableBorrowRate {
+        #[ink(topic)]
+        reserve: AccountId,
+        #[ink(topic)]
+        user: AccountId,
+        new_rate: u128,
+    }
+
+    /**
+     * @dev Emitted on set_user_use_reserve_as_collateral(), or when a user first receives sTokens of a
+     * reserve, which back their debt from then on
+     * @param reserve The address of the underlying asset of the reserve
+     * @param user The address of the user enabling the usage as collateral
+     **/
+    #[ink(event)]
+    pub struct ReserveUsedAsCollateralEnabled {
+        #[ink(topic)]
+        reserve: AccountId,
+        #[ink(topic)]
+        user: AccountId,
+    }
+
+    /**
+     * @dev Emitted on set_user_use_reserve_as_collateral(), or when a user no longer holds sTokens
+     * of a reserve to back their debt
+     * @param reserve The address of the underlying asset of the reserve
+     * @param user The address of the user disabling the usage as collateral
+     **/
+    #[ink(event)]
+    pub struct ReserveUsedAsCollateralDisabled {
+        #[ink(topic)]
+        reserve: AccountId,
+        #[ink(topic)]
+        user: AccountId,
+    }
+
+    /**
+     * @dev Emitted on init_reserve()
+     * @param asset The address of the underlying asset of the reserve
+     * @param stoken The address of the associated sToken
+     * @param stable_debt_token The address of the associated stable rate debt token
+     * @param variable_debt_token The address of the associated variable rate debt token
+     **/
+    #[ink(event)]
+    pub struct ReserveInitialized {
+        #[ink(topic)]
+        asset: AccountId,
+        #[ink(topic)]
+        stoken: AccountId,
+        stable_debt_token: AccountId,
+        variable_debt_token: AccountId,
+    }
+
+    /**
+     * @dev Emitted on mint_to_treasury()
+     * @param reserve The address of the underlying asset of the reserve
+     * @param amount_minted The amount of sTokens minted to the treasury
+     **/
+    #[ink(event)]
+    pub struct MintedToTreasury {
+        #[ink(topic)]
+        reserve: AccountId,
+        amount_minted: Balance,
+    }
+
+    /**
+     * @dev Emitted when borrowing is enabled on a reserve
+     * @param asset The address of the underlying asset of the reserve
+     * @param stable_rate_enabled `true` if stable rate borrowing is enabled, `false` otherwise
+     **/
+    #[ink(event)]
+    pub struct BorrowingEnabledOnReserve {
+        #[ink(topic)]
+        asset: AccountId,
+        stable_rate_enabled: bool,
+    }
+
+    /**
+     * @dev Emitted when borrowing is disabled on a reserve
+     * @param asset The address of the underlying asset of the reserve
+     **/
+    #[ink(event)]
+    pub struct BorrowingDisabledOnReserve {
+        #[ink(topic)]
+        asset: AccountId,
+    }
+
+    /**
+     * @dev Emitted when stable rate borrowing is enabled on a reserve
+     * @param asset The address of the underlying asset of the reserve
+     **/
+    #[ink(event)]
+    pub struct StableRateEnabledOnReserve {
+        #[ink(topic)]
+        asset: AccountId,
+    }
+
+    /**
+     * @dev Emitted when stable rate borrowing is disabled on a reserve
+     * @param asset The address of the underlying asset of the reserve
+     **/
+    #[ink(event)]
+    pub struct StableRateDisabledOnReserve {
+        #[ink(topic)]
+        asset: AccountId,
+    }
+
+    /**
+     * @dev Emitted when a borrower is liquidated.
+     * @param collateral_asset The address of the underlying asset used as collateral, to receive as result of the liquidation
+     * @param debt_asset The address of the underlying borrowed asset to be repaid with the liquidation
+     * @param user The address of the borrower getting liquidated
+     * @param debt_to_cover The debt amount of borrowed `asset` the liquidator wants to cover
+     * @param liquidated_collateral_amount The amount of collateral received by the liiquidator
+     * @param liquidator The address of the liquidator
+     * @param receive_stoken `true` if the liquidators wants to receive the collateral sTokens, `false` if he wants
+     * to receive the underlying collateral asset directly
+     **/
+    #[ink(event)]
+    pub struct LiquidationCall {
+        #[ink(topic)]
+        collateral_asset: AccountId,
+        #[ink(topic)]
+        debt_asset: AccountId,
+        #[ink(topic)]
+        user: AccountId,
+        debt_to_cover: Balance,
+        liquidated_collateral_amount: Balance,
+        liquidator: AccountId,
+        receive_stoken: bool,
+    }
+
+    /**
+     * @dev Emitted when the state of a reserve is updated
+     * @param reserve The address of the underlying asset of the reserve
+     * @param liquidity_rate The new liquidity rate
+     * @param stable_borrow_rate The new stable borrow rate
+     * @param variable_borrow_rate The new variable borrow rate
+     * @param liquidity_index The new liquidity index
+     * @param variable_borrow_index The new variable borrow index
+     **/
+    #[ink(event)]
+    pub struct ReserveDataUpdated {
+        #[ink(topic)]
+        reserve: AccountId,
+        liquidity_rate: u128,
+        stable_borrow_rate: u128,
+        variable_borrow_rate: u128,
+        liquidity_index: u128,
+        variable_borrow_index: u128,
+    }
+
+    /**
+     * @dev Emitted when a configuration role is handed to another account
+     * @param role The role transferred
+     * @param previous_account The account holding the role until now
+     * @param new_account The account holding the role from now on
+     **/
+    #[ink(event)]
+    pub struct RoleTransferred {
+        #[ink(topic)]
+        role: Role,
+        #[ink(topic)]
+        previous_account: AccountId,
+        #[ink(topic)]
+        new_account: AccountId,
+    }
+
+    /**
+     * @dev Emitted when the pool is paused, stopping every action on the reserves
+     * @param emergency_admin The address pausing the pool
+     **/
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        emergency_admin: AccountId,
+    }
+
+    /**
+     * @dev Emitted when the pool is unpaused
+     * @param emergency_admin The address unpausing the pool
+     **/
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        emergency_admin: AccountId,
+    }
+
+    /**
+     * @dev Emitted when a reserve is activated
+     * @param asset The address of the underlying asset of the reserve
+     **/
+    #[ink(event)]
+    pub struct ReserveActivated {
+        #[ink(topic)]
+        asset: AccountId,
+    }
+
+    /**
+     * @dev Emitted when a reserve is deactivated
+     * @param asset The address of the underlying asset of the reserve
+     **/
+    #[ink(event)]
+    pub struct ReserveDeactivated {
+        #[ink(topic)]
+        asset: AccountId,
+    }
+
+    /**
+     * @dev Emitted when a reserve is frozen
+     * @param asset The address of the underlying asset of the reserve
+     **/
+    #[ink(event)]
+    pub struct ReserveFrozen {
+        #[ink(topic)]
+        asset: AccountId,
+    }
+
+    /**
+     * @dev Emitted when a reserve is unfrozen
+     * @param asset The address of the underlying asset of the reserve
+     **/
+    #[ink(event)]
+    pub struct ReserveUnfrozen {
+        #[ink(topic)]
+        asset: AccountId,
     }
 
     #[ink(storage)]
     pub struct Lendingpool {
-        // DOT
-        reserve: ReserveData,
+        // underlying asset => reserve
+        reserves: StorageHashMap<AccountId, ReserveData>,
+        // listed underlying assets, in initialization order
+        reserves_list: StorageVec<AccountId>,
+        // user => reserves they borrow or use as collateral
+        users_config: StorageHashMap<AccountId, UserConfiguration>,
 
-        users_data: StorageHashMap<AccountId, UserReserveData>,
-        //store the delegateallowance
-        delegate_allowance: StorageHashMap<(AccountId, AccountId), Balance>,
+        // lists reserves, manages the oracles and hands out the roles
+        pool_admin: AccountId,
+        // tunes the rates and risk parameters of the reserves
+        risk_admin: AccountId,
+        // pauses the pool
+        emergency_admin: AccountId,
+        // while set, no action can be taken on any reserve
+        paused: bool,
+
+        // prices of the reserves in a common base currency
+        price_oracle: AccountId,
+        // queried when the price oracle has no fresh price
+        fallback_oracle: Option<AccountId>,
+        // age in milliseconds after which an oracle price is stale
+        max_price_age: u64,
+
+        // receives the reserve factor share of the borrow interest, as sTokens
+        treasury: AccountId,
     }
 
     impl Lendingpool {
         #[ink(constructor)]
-        pub fn new(stoken: AccountId, debt_token: AccountId) -> Self {
+        pub fn new(price_oracle: AccountId, treasury: AccountId) -> Self {
             Self {
-                reserve: ReserveData {
-                    stable_liquidity_rate: 18,
-                    stable_borrow_rate: 10,
+                reserves: StorageHashMap::new(),
+                reserves_list: StorageVec::new(),
+                users_config: StorageHashMap::new(),
+                pool_admin: Self::env().caller(),
+                risk_admin: Self::env().caller(),
+                emergency_admin: Self::env().caller(),
+                paused: false,
+                price_oracle,
+                fallback_oracle: None,
+                max_price_age: DEFAULT_MAX_PRICE_AGE,
+                treasury,
+            }
+        }
+
+        /**
+         * @dev Initializes a reserve, activating it and assigning its sToken and debt tokens
+         * - Only callable by the pool admin
+         * - The pool must be the pool of the sToken and of both debt tokens, as it mints and burns them
+         * @param asset The address of the underlying asset of the reserve
+         * @param stoken The address of the sToken that will be assigned to the reserve
+         * @param stable_debt_token The address of the stable debt token that will be assigned to the reserve
+         * @param variable_debt_token The address of the variable debt token that will be assigned to the reserve
+         * @param params The interest rate strategy and liquidation parameters of the reserve
+         **/
+        #[ink(message)]
+        pub fn init_reserve(
+            &mut self,
+            asset: AccountId,
+            stoken: AccountId,
+            stable_debt_token: AccountId,
+            variable_debt_token: AccountId,
+            params: InitReserveParams,
+        ) -> Result<()> {
+            self.only_pool_admin()?;
+            if self.reserves.contains_key(&asset) {
+                return Err(LendingError::RlReserveAlreadyInitialized);
+            }
+            if self.reserves_list.len() >= MAX_NUMBER_RESERVES {
+                return Err(LendingError::LpNoMoreReservesAllowed);
+            }
+            params.interest_rate_strategy.validate()?;
+            let mut configuration = ReserveConfiguration::default();
+            configuration.set_active(true);
+            configuration.set_borrowing_enabled(true);
+            configuration.set_stable_borrowing_enabled(params.stable_borrow_rate_enabled);
+            configuration.set_decimals(params.decimals)?;
+            configuration.set_reserve_factor(params.reserve_factor)?;
+            configuration.set_borrow_cap(params.borrow_cap)?;
+            configuration.set_supply_cap(params.supply_cap)?;
+            configuration.set_collateral_params(
+                params.ltv,
+                params.liquidation_threshold,
+                params.liquidation_bonus,
+            )?;
+
+            self.reserves.insert(
+                asset,
+                ReserveData {
+                    liquidity_index: RAY,
+                    variable_borrow_index: RAY,
+                    current_liquidity_rate: 0,
+                    current_variable_borrow_rate: params.interest_rate_strategy.base_borrow_rate,
+                    current_stable_borrow_rate: params
+                        .interest_rate_strategy
+                        .base_stable_borrow_rate,
+                    last_update_timestamp: Self::env().block_timestamp(),
+                    accrued_to_treasury: 0,
+                    interest_rate_strategy: params.interest_rate_strategy,
+                    configuration,
                     stoken_address: stoken,
-                    stable_debt_token_address: debt_token,
+                    stable_debt_token_address: stable_debt_token,
+                    variable_debt_token_address: variable_debt_token,
+                    id: self.reserves_list.len() as u8,
                 },
-                users_data: StorageHashMap::new(),
-                delegate_allowance: StorageHashMap::new(),
-            }
+            );
+            self.reserves_list.push(asset);
+
+            self.env().emit_event(ReserveInitialized {
+                asset,
+                stoken,
+                stable_debt_token,
+                variable_debt_token,
+            });
+            Ok(())
         }
 
         /// * @dev Deposits an `amount` of underlying asset into the reserve, receiving in return overlying aTokens.
         /// * - E.g. User deposits 100 USDC and gets in return 100 aUSDC
+        /// * - The caller must have approved the pool to spend `amount` of `asset`
         /// * @param asset The address of the underlying asset to deposit
         /// * @param amount The amount to be deposited
         /// * @param onBehalfOf The address that will receive the aTokens, same as msg.sender if the user
         /// *   wants to receive them on his own wallet, or a different address if the beneficiary of aTokens
         /// *   is a different wallet
-        #[ink(message, payable)]
-        pub fn deposit(&mut self, on_behalf_of: Option<AccountId>) {
+        #[ink(message)]
+        pub fn deposit(
+            &mut self,
+            asset: AccountId,
+            amount: Balance,
+            on_behalf_of: Option<AccountId>,
+        ) -> Result<()> {
+            self.when_not_paused()?;
             let sender = self.env().caller();
             let mut receiver = sender;
             if let Some(behalf) = on_behalf_of {
                 receiver = behalf;
             }
-            let amount = self.env().transferred_balance();
-            assert_ne!(amount, 0, "{}", VL_INVALID_AMOUNT);
-
-            let mut stoken: IERC20 = FromAccountId::from_account_id(self.reserve.stoken_address);
-            let debttoken: IERC20 =
-                FromAccountId::from_account_id(self.reserve.stable_debt_token_address);
-
-            let entry = self.users_data.entry(receiver);
-            let reserve_data = entry.or_insert(Default::default());
-            // user balance should always be stoken - debttoken
-            let user_balance = stoken.balance_of(receiver) - debttoken.balance_of(receiver);
-
-            if reserve_data.last_update_timestamp != 0 {
-                let interval = Self::env().block_timestamp() - reserve_data.last_update_timestamp;
-                let interest = user_balance * interval as u128 * self.reserve.stable_liquidity_rate
-                    / (100 * 365 * 24 * 3600 * 1000);
-                if interest > 0 {
-                    reserve_data.cumulated_liquidity_interest += interest;
-                    reserve_data.last_update_timestamp = Self::env().block_timestamp();
-                }
-            } else {
-                reserve_data.last_update_timestamp = Self::env().block_timestamp();
+            if amount == 0 {
+                return Err(LendingError::VlInvalidAmount);
+            }
+            let mut reserve = self.get_reserve(asset)?;
+            if !reserve.configuration.is_active() {
+                return Err(LendingError::VlNoActiveReserve);
+            }
+            if reserve.configuration.is_frozen() {
+                return Err(LendingError::VlReserveFrozen);
             }
+            self.update_state(&mut reserve, Self::env().block_timestamp())?;
 
-            assert!(stoken.mint(receiver, amount).is_ok());
+            let amount_scaled = ray_div(amount, reserve.liquidity_index)?;
+            if amount_scaled == 0 {
+                return Err(LendingError::CtInvalidMintAmount);
+            }
+
+            let mut underlying: Token = FromAccountId::from_account_id(asset);
+            let mut stoken: Stoken = FromAccountId::from_account_id(reserve.stoken_address);
+            if reserve.configuration.supply_cap() != 0 {
+                // the sTokens not yet minted to the treasury count in the supply
+                let total_supply = ray_mul(
+                    stoken
+                        .scaled_total_supply()
+                        .checked_add(reserve.accrued_to_treasury)
+                        .ok_or(LendingError::MathAdditionOverflow)?,
+                    reserve.liquidity_index,
+                )?
+                .checked_add(amount)
+                .ok_or(LendingError::MathAdditionOverflow)?;
+                if reserve.configuration.exceeds_supply_cap(total_supply) {
+                    return Err(LendingError::VlSupplyCapExceeded);
+                }
+            }
+            let is_first_deposit = stoken.scaled_balance_of(receiver) == 0;
+            underlying.transfer_from(sender, self.env().account_id(), amount)?;
+            // the funds are in the pool from here on: returning an error would not roll the
+            // transfer back, so any failure traps and reverts the whole call
+            revert_on_error(
+                "deposit",
+                stoken.mint(receiver, amount, reserve.liquidity_index),
+            );
+            if is_first_deposit {
+                self.set_user_collateral(asset, reserve.id, receiver, true);
+            }
+            revert_on_error("deposit", self.update_interest_rates(asset, &mut reserve));
+            self.reserves.insert(asset, reserve);
 
             self.env().emit_event(Deposit {
+                reserve: asset,
                 user: sender,
                 on_behalf_of: receiver,
                 amount,
             });
+            Ok(())
         }
 
+        /**
+         * @dev Returns the state and configuration of the reserve, as of its last update
+         * - The indexes and rates are the ones stored at `last_update_timestamp`, use
+         *   get_reserve_normalized_income() and get_reserve_normalized_variable_debt() for the
+         *   indexes at the current block
+         * @param asset The address of the underlying asset of the reserve
+         **/
         #[ink(message)]
-        pub fn get_reserve_data(&self, user: AccountId) -> Option<UserReserveData> {
-            self.users_data.get(&user).cloned()
+        pub fn get_reserve_data(&self, asset: AccountId) -> Option<ReserveData> {
+            self.reserves.get(&asset).cloned()
         }
 
+        /**
+         * @dev Returns the liquidity and the debt of the reserve at the current block, interest included
+         * @param asset The address of the underlying asset of the reserve
+         * @return available_liquidity The underlying asset held by the pool, available to borrow and withdraw
+         * @return total_stable_debt The stable debt of all the borrowers
+         * @return total_variable_debt The variable debt of all the borrowers
+         * @return average_stable_rate The average stable rate of the borrowers, in ray
+         * @return utilization_rate The share of the total liquidity borrowed, in ray
+         **/
         #[ink(message)]
-        pub fn get_scaled_balance(&self, user: AccountId) -> Balance {
-            let reserve_data = self
-                .users_data
-                .get(&user)
-                .cloned()
-                .unwrap_or(Default::default());
+        pub fn get_reserve_liquidity_data(&self, asset: AccountId) -> Option<ReserveLiquidityData> {
+            let mut reserve = self.reserves.get(&asset).cloned()?;
+            reserve.update_state(Self::env().block_timestamp()).ok()?;
+            self.reserve_liquidity(asset, &reserve).ok()
+        }
 
-            let stoken: IERC20 = FromAccountId::from_account_id(self.reserve.stoken_address);
-            let debttoken: IERC20 =
-                FromAccountId::from_account_id(self.reserve.stable_debt_token_address);
+        /**
+         * @dev Returns the position of a user in a reserve, interest included
+         * @param asset The address of the underlying asset of the reserve
+         * @param user The address of the user
+         **/
+        #[ink(message)]
+        pub fn get_user_reserve_data(
+            &self,
+            asset: AccountId,
+            user: AccountId,
+        ) -> Option<UserReserveData> {
+            let reserve = self.reserves.get(&asset)?;
+            let now = Self::env().block_timestamp();
 
-            // user balance should always be stoken - debttoken
-            let mut user_balance = stoken
-                .balance_of(user)
-                .saturating_sub(debttoken.balance_of(user));
+            let stoken: Stoken = FromAccountId::from_account_id(reserve.stoken_address);
+            let scaled_stoken_balance = stoken.scaled_balance_of(user);
+            let stable_debt_token: StableDebtToken =
+                FromAccountId::from_account_id(reserve.stable_debt_token_address);
+            let (principal_stable_debt, current_stable_debt) =
+                self.stable_debt_of(reserve, user).ok()?;
+            let (scaled_variable_debt, current_variable_debt) =
+                self.variable_debt_of(reserve, user, now).ok()?;
 
-            if reserve_data.last_update_timestamp != 0 {
-                let interval = Self::env().block_timestamp() - reserve_data.last_update_timestamp;
-                let interest = user_balance * interval as u128 * self.reserve.stable_liquidity_rate
-                    / (100 * 365 * 24 * 3600 * 1000);
-                user_balance += interest;
-            }
-            user_balance
+            Some(UserReserveData {
+                current_stoken_balance: ray_mul(
+                    scaled_stoken_balance,
+                    reserve.normalized_income(now).ok()?,
+                )
+                .ok()?,
+                scaled_stoken_balance,
+                current_stable_debt,
+                principal_stable_debt,
+                stable_borrow_rate: stable_debt_token.get_user_stable_rate(user),
+                current_variable_debt,
+                scaled_variable_debt,
+            })
+        }
+
+        /// Returns the deposit of `user` in the reserve net of his debt, interest included.
+        #[ink(message)]
+        pub fn get_scaled_balance(&self, asset: AccountId, user: AccountId) -> Balance {
+            self.get_user_reserve_data(asset, user)
+                .map(|data| {
+                    data.current_stoken_balance
+                        .saturating_sub(data.current_stable_debt)
+                        .saturating_sub(data.current_variable_debt)
+                })
+                .unwrap_or(0)
+        }
+
+        /**
+         * @dev Returns the user account data across all the reserves, as checked by borrows, withdrawals
+         * and liquidations
+         * @param user The address of the user
+         * @return total_collateral The collateral of the user, in the base currency of the price oracle
+         * @return total_debt The debt of the user, interest included, in the base currency
+         * @return available_borrows The borrowing power left to the user, in the base currency
+         * @return current_liquidation_threshold The liquidation threshold of the user, in basis points
+         * @return ltv The loan to value of the user, in basis points
+         * @return health_factor The health factor of the user, in wad
+         **/
+        #[ink(message)]
+        pub fn get_user_account_data(&self, user: AccountId) -> Result<UserAccountData> {
+            self.calculate_user_account_data(user)
         }
 
         /// * @dev Withdraws an `amount` of underlying asset from the reserve, burning the equivalent aTokens owned
         /// * E.g. User has 100 aUSDC, calls withdraw() and receives 100 USDC, burning the 100 aUSDC
         /// * @param asset The address of the underlying asset to withdraw
         /// * @param amount The underlying amount to be withdrawn
-        /// *   - Send the value type(uint256).max in order to withdraw the whole aToken balance
+        /// *   - Send the value u128::MAX in order to withdraw the whole aToken balance
         /// * @param to Address that will receive the underlying, same as msg.sender if the user
         /// *   wants to receive it on his own wallet, or a different address if the beneficiary is a
         /// *   different wallet
         /// * @return The final amount withdrawn
         #[ink(message)]
-        pub fn withdraw(&mut self, amount: Balance, to: Option<AccountId>) {
-            assert_ne!(amount, 0, "{}", VL_INVALID_AMOUNT);
+        pub fn withdraw(
+            &mut self,
+            asset: AccountId,
+            amount: Balance,
+            to: Option<AccountId>,
+        ) -> Result<()> {
+            self.when_not_paused()?;
+            if amount == 0 {
+                return Err(LendingError::VlInvalidAmount);
+            }
             let sender = self.env().caller();
             let mut receiver = sender;
             if let Some(behalf) = to {
                 receiver = behalf;
             }
+            let mut reserve = self.get_reserve(asset)?;
+            if !reserve.configuration.is_active() {
+                return Err(LendingError::VlNoActiveReserve);
+            }
+            self.update_state(&mut reserve, Self::env().block_timestamp())?;
 
-            let mut stoken: IERC20 = FromAccountId::from_account_id(self.reserve.stoken_address);
-            let debttoken: IERC20 =
-                FromAccountId::from_account_id(self.reserve.stable_debt_token_address);
-            //user balance should always be stoken - debttoken
-            let user_balance = stoken.balance_of(sender) - debttoken.balance_of(sender);
-            let reserve_data = self
-                .users_data
-                .get_mut(&sender)
-                .expect("user config does not exist");
-            let interval = Self::env().block_timestamp() - reserve_data.last_update_timestamp;
-
-            let interest = user_balance * interval as u128 * self.reserve.stable_liquidity_rate
-                / (100 * 365 * 24 * 3600 * 1000);
-            if interest > 0 {
-                reserve_data.cumulated_liquidity_interest += interest;
-                reserve_data.last_update_timestamp = Self::env().block_timestamp();
-            }
-
-            let cur_user_balance = (stoken.balance_of(receiver)  - debttoken.balance_of(receiver)* 100/75) + reserve_data.cumulated_liquidity_interest ;
-            assert!(
-                amount <= cur_user_balance,
-                "{}",
-                VL_NOT_ENOUGH_AVAILABLE_USER_BALANCE
-            );
+            let mut underlying: Token = FromAccountId::from_account_id(asset);
+            let mut stoken: Stoken = FromAccountId::from_account_id(reserve.stoken_address);
 
-            if amount <= reserve_data.cumulated_liquidity_interest {
-                reserve_data.cumulated_liquidity_interest -= amount;
+            let user_balance = ray_mul(stoken.scaled_balance_of(sender), reserve.liquidity_index)?;
+            if user_balance == 0 {
+                return Err(LendingError::VlUnderlyingBalanceNotGreaterThan0);
+            }
+            let amount_to_withdraw = if amount == Balance::MAX {
+                user_balance
             } else {
-                let rest = amount - reserve_data.cumulated_liquidity_interest;
-                reserve_data.cumulated_liquidity_interest = 0;
-                stoken.burn(sender, rest).expect("sToken burn failed");
+                amount
+            };
+
+            if amount_to_withdraw > user_balance {
+                return Err(LendingError::VlNotEnoughAvailableUserBalance);
+            }
+
+            // the collateral left must still cover the debt of all the reserves
+            if !self.balance_decrease_allowed(asset, sender, amount_to_withdraw)? {
+                return Err(LendingError::VlTransferNotAllowed);
+            }
+            if amount_to_withdraw > underlying.balance_of(self.env().account_id()) {
+                return Err(LendingError::VlCurrentAvailableLiquidityNotEnough);
             }
-            self.env()
-                .transfer(receiver, amount)
-                .expect("transfer failed");
+
+            stoken.burn(sender, amount_to_withdraw, reserve.liquidity_index)?;
+            // the sTokens are burnt from here on: returning an error would not roll the burn
+            // back, so any failure traps and reverts the whole call
+            revert_on_error(
+                "withdraw",
+                underlying.transfer(receiver, amount_to_withdraw),
+            );
+            if amount_to_withdraw == user_balance {
+                self.set_user_collateral(asset, reserve.id, sender, false);
+            }
+            revert_on_error("withdraw", self.update_interest_rates(asset, &mut reserve));
+            self.reserves.insert(asset, reserve);
 
             self.env().emit_event(Withdraw {
+                reserve: asset,
                 user: sender,
                 to: receiver,
-                amount,
+                amount: amount_to_withdraw,
             });
+            Ok(())
         }
 
         /**
@@ -261,173 +738,1688 @@ mod lendingpool {
          * already deposited enough collateral, or he was given enough allowance by a credit delegator on the
          * corresponding debt token
          * - E.g. User borrows 100 USDC passing as `onBehalfOf` his own address, receiving the 100 USDC in his wallet
-         *   and 100 stable debt tokens
+         *   and 100 stable/variable debt tokens, depending on the `interest_rate_mode`
+         * @param asset The address of the underlying asset to borrow
          * @param amount The amount to be borrowed
+         * @param interest_rate_mode The interest rate mode at which the user wants to borrow: Stable or Variable
          * @param onBehalfOf Address of the user who will receive the debt. Should be the address of the borrower itself
          * calling the function if he wants to borrow against his own collateral, or the address of the credit delegator
          * if he has been given credit delegation allowance
          **/
         #[ink(message)]
-        pub fn borrow(&mut self, amount: Balance, on_behalf_of: AccountId) {
-            assert_ne!(amount, 0, "{}", VL_INVALID_AMOUNT);
-
-            let sender = self.env().caller();
-            let receiver = on_behalf_of;
-
-            let stoken: IERC20 = FromAccountId::from_account_id(self.reserve.stoken_address);
-            let mut dtoken: IERC20 =
-                FromAccountId::from_account_id(self.reserve.stable_debt_token_address);
-
-            // credit delegation allowances check
-            let credit_balance = self
-                .delegate_allowance
-                .get(&(receiver, sender))
-                .copied()
-                .unwrap_or(0);
-            assert!(
-                amount <= credit_balance,
-                "{}",
-                VL_NOT_ENOUGH_AVAILABLE_USER_BALANCE
-            );
-
-            // stoken - debetoken
-            let liquidation_threshold =
-                stoken.balance_of(receiver)  - dtoken.balance_of(receiver)* 100/75 ;
-            assert!(
-                amount <= liquidation_threshold,
-                "{}",
-                LP_NOT_ENOUGH_LIQUIDITY_TO_BORROW
-            );
-
-            let reserve_data = self
-                .users_data
-                .get_mut(&receiver)
-                .expect("user config does not exist");
-            let interval = Self::env().block_timestamp() - reserve_data.last_update_timestamp;
-
-            // borrow update depositor interest
-            let user_balance = stoken.balance_of(receiver) - dtoken.balance_of(receiver) ;
-            let interest = user_balance * interval as u128 * self.reserve.stable_liquidity_rate
-                / (100 * 365 * 24 * 3600 * 1000);
-            reserve_data.cumulated_liquidity_interest += interest;
-            reserve_data.last_update_timestamp = Self::env().block_timestamp();
-
-            // update borrow info
-            let entry_sender = self.users_data.entry(sender);
-            let reserve_data_sender = entry_sender.or_insert(Default::default());
-            let interval =
-                Self::env().block_timestamp() - reserve_data_sender.last_update_timestamp;
-            reserve_data_sender.cumulated_stable_borrow_interest += reserve_data_sender
-                .borrow_balance
-                * interval as u128
-                * self.reserve.stable_borrow_rate
-                / (100 * 365 * 24 * 3600 * 1000);
-            reserve_data_sender.borrow_balance += amount;
-            reserve_data_sender.last_update_timestamp = Self::env().block_timestamp();
-
-            // update delegate amount
-            self.delegate_allowance
-                .insert((receiver, sender), credit_balance - amount);
-            // dtoken
-            //     .transfer_from(receiver, sender, credit_balance - amount)
-            //     .expect("transfer failed");
-
-            // mint debt token to receiver
-            assert!(dtoken.mint(receiver, amount).is_ok());
-
-            // transfer reserve asset to sender
-            self.env()
-                .transfer(sender, amount)
-                .expect("transfer failed");
-
-            self.env().emit_event(Borrow {
-                user: sender,
+        pub fn borrow(
+            &mut self,
+            asset: AccountId,
+            amount: Balance,
+            interest_rate_mode: InterestRateMode,
+            on_behalf_of: AccountId,
+        ) -> Result<()> {
+            self.when_not_paused()?;
+            self.execute_borrow(
+                asset,
+                self.env().caller(),
                 on_behalf_of,
                 amount,
-            });
+                interest_rate_mode,
+                true,
+            )
         }
 
         /**
          * @notice Repays a borrowed `amount` on a specific reserve, burning the equivalent debt tokens owned
-         * - E.g. User repays 100 USDC, burning 100 stable debt tokens of the `onBehalfOf` address
+         * - E.g. User repays 100 USDC, burning 100 variable/stable debt tokens of the `onBehalfOf` address
+         * - The caller must have approved the pool to spend `amount` of `asset`
+         * @param asset The address of the borrowed underlying asset previously borrowed
          * @param amount The amount to repay
-         * - Send the value type(uint256).max in order to repay the whole debt for `asset` on the specific `debtMode`
+         * - Send the value u128::MAX in order to repay the whole debt for `asset` on the specific `rate_mode`
+         * @param rate_mode The interest rate mode of the debt the user wants to repay: Stable or Variable
          * @param onBehalfOf Address of the user who will get his debt reduced/removed. Should be the address of the
          * user calling the function if he wants to reduce/remove his own debt, or the address of any other
          * other borrower whose debt should be removed
          * @return The final amount repaid
          **/
-        #[ink(message, payable)]
-        pub fn repay(&mut self, on_behalf_of: AccountId) {
+        #[ink(message)]
+        pub fn repay(
+            &mut self,
+            asset: AccountId,
+            amount: Balance,
+            rate_mode: InterestRateMode,
+            on_behalf_of: AccountId,
+        ) -> Result<()> {
+            self.when_not_paused()?;
             let sender = self.env().caller();
             let recevier = on_behalf_of;
 
-            // get repay amount
-            let amount = self.env().transferred_balance();
-            assert_ne!(amount, 0, "{}", VL_INVALID_AMOUNT);
-
-            let mut dtoken: IERC20 =
-                FromAccountId::from_account_id(self.reserve.stable_debt_token_address);
-
-            // update interest
-            let reserve_data_sender = self
-                .users_data
-                .get_mut(&sender)
-                .expect("you have not borrow any dot");
-            let interval =
-                Self::env().block_timestamp() - reserve_data_sender.last_update_timestamp;
-            reserve_data_sender.cumulated_stable_borrow_interest += reserve_data_sender
-                .borrow_balance
-                * interval as u128
-                * self.reserve.stable_borrow_rate
-                / (100 * 365 * 24 * 3600 * 1000);
-            reserve_data_sender.borrow_balance += amount;
-            reserve_data_sender.last_update_timestamp = Self::env().block_timestamp();
-
-            if amount <= reserve_data_sender.cumulated_stable_borrow_interest {
-                reserve_data_sender.cumulated_stable_borrow_interest -= amount
-            } else {
-                let rest = amount - reserve_data_sender.cumulated_stable_borrow_interest;
-                reserve_data_sender.cumulated_stable_borrow_interest = 0;
-                dtoken.burn(recevier, rest).expect("debt token burn failed");
+            if amount == 0 {
+                return Err(LendingError::VlInvalidAmount);
+            }
+            if amount == Balance::MAX && sender != recevier {
+                return Err(LendingError::VlNoExplicitAmountToRepayOnBehalf);
+            }
+            let mut reserve = self.get_reserve(asset)?;
+            if !reserve.configuration.is_active() {
+                return Err(LendingError::VlNoActiveReserve);
             }
+            let now = Self::env().block_timestamp();
+            self.update_state(&mut reserve, now)?;
+
+            let mut underlying: Token = FromAccountId::from_account_id(asset);
+            let (_, stable_debt) = self.stable_debt_of(&reserve, recevier)?;
+            let (_, variable_debt) = self.variable_debt_of(&reserve, recevier, now)?;
+            let debt = match rate_mode {
+                InterestRateMode::Stable => stable_debt,
+                InterestRateMode::Variable => variable_debt,
+            };
+            if debt == 0 {
+                return Err(LendingError::VlNoDebtOfSelectedType);
+            }
+            let payback_amount = if amount < debt { amount } else { debt };
+
+            underlying.transfer_from(sender, self.env().account_id(), payback_amount)?;
+            // the payment is in the pool from here on: returning an error would not roll the
+            // transfer back, so any failure traps and reverts the whole call
+            let burnt = match rate_mode {
+                InterestRateMode::Stable => {
+                    self.burn_stable_debt(&reserve, recevier, payback_amount)
+                }
+                InterestRateMode::Variable => {
+                    self.burn_variable_debt(&reserve, recevier, payback_amount, now)
+                }
+            };
+            revert_on_error("repay", burnt);
+            if stable_debt.saturating_add(variable_debt) == payback_amount {
+                self.set_user_borrowing(reserve.id, recevier, false);
+            }
+            revert_on_error("repay", self.update_interest_rates(asset, &mut reserve));
+            self.reserves.insert(asset, reserve);
 
             self.env().emit_event(Repay {
+                reserve: asset,
                 receiver: on_behalf_of,
                 repayer: sender,
-                amount,
+                amount: payback_amount,
             });
+            Ok(())
         }
 
         /**
-         * @dev delgator can delegate some their own credits which get by deposit funds to delegatee
-         * @param delegatee who can borrow without collateral
-         * @param amount
-         */
+         * @dev Allows a borrower to swap his debt between stable and variable mode, or viceversa
+         * @param asset The address of the underlying asset borrowed
+         * @param rate_mode The rate mode that the user wants to swap from
+         **/
         #[ink(message)]
-        pub fn delegate(&mut self, delegatee: AccountId, amount: Balance) {
-            let delegator = self.env().caller();
-            self.delegate_allowance
-                .insert((delegator, delegatee), amount);
+        pub fn swap_borrow_rate_mode(
+            &mut self,
+            asset: AccountId,
+            rate_mode: InterestRateMode,
+        ) -> Result<()> {
+            self.when_not_paused()?;
+            let user = self.env().caller();
+            let mut reserve = self.get_reserve(asset)?;
+            if !reserve.configuration.is_active() {
+                return Err(LendingError::VlNoActiveReserve);
+            }
+            if reserve.configuration.is_frozen() {
+                return Err(LendingError::VlReserveFrozen);
+            }
+            let now = Self::env().block_timestamp();
+            self.update_state(&mut reserve, now)?;
+
+            let (_, stable_debt) = self.stable_debt_of(&reserve, user)?;
+            let (_, variable_debt) = self.variable_debt_of(&reserve, user, now)?;
+            let new_rate_mode = match rate_mode {
+                InterestRateMode::Stable => {
+                    if stable_debt == 0 {
+                        return Err(LendingError::VlNoStableRateLoanInReserve);
+                    }
+                    self.burn_stable_debt(&reserve, user, stable_debt)?;
+                    // the stable debt is burnt from here on: returning an error would not roll
+                    // the burn back, so any failure traps and reverts the whole call
+                    revert_on_error(
+                        "swap",
+                        self.mint_variable_debt(&reserve, user, user, stable_debt),
+                    );
+                    InterestRateMode::Variable
+                }
+                InterestRateMode::Variable => {
+                    if variable_debt == 0 {
+                        return Err(LendingError::VlNoVariableRateLoanInReserve);
+                    }
+                    self.validate_stable_borrow(&reserve, user, variable_debt, now)?;
+                    self.burn_variable_debt(&reserve, user, variable_debt, now)?;
+                    // the variable debt is burnt from here on: returning an error would not roll
+                    // the burn back, so any failure traps and reverts the whole call
+                    let rate = reserve.current_stable_borrow_rate;
+                    revert_on_error(
+                        "swap",
+                        self.mint_stable_debt(&reserve, user, user, variable_debt, rate),
+                    );
+                    InterestRateMode::Stable
+                }
+            };
+            revert_on_error("swap", self.update_interest_rates(asset, &mut reserve));
+            self.reserves.insert(asset, reserve);
+
+            self.env().emit_event(Swap {
+                reserve: asset,
+                user,
+                rate_mode: new_rate_mode,
+            });
+            Ok(())
         }
 
+        /**
+         * @dev Rebalances the stable interest rate of a user to the current stable rate defined on the reserve.
+         * - Users can be rebalanced if the utilisation of the reserve is above 95% and their stable rate
+         *   is below the current liquidity rate, i.e. the depositors earn more than they pay
+         * - Anyone can call it, the debt of the user is kept and only repriced
+         * @param asset The address of the underlying asset borrowed
+         * @param user The address of the user to be rebalanced
+         **/
         #[ink(message)]
-        pub fn delegate_amount(&self, delegator: AccountId, delegatee: AccountId) -> Balance {
-            self.delegate_allowance
-                .get(&(delegator, delegatee))
-                .copied()
-                .unwrap_or(0u128)
+        pub fn rebalance_stable_borrow_rate(
+            &mut self,
+            asset: AccountId,
+            user: AccountId,
+        ) -> Result<()> {
+            self.when_not_paused()?;
+            let mut reserve = self.get_reserve(asset)?;
+            if !reserve.configuration.is_active() {
+                return Err(LendingError::VlNoActiveReserve);
+            }
+            let now = Self::env().block_timestamp();
+            self.update_state(&mut reserve, now)?;
+
+            let (_, stable_debt) = self.stable_debt_of(&reserve, user)?;
+            if stable_debt == 0 {
+                return Err(LendingError::LpNotEnoughStableBorrowBalance);
+            }
+
+            let stable_debt_token: StableDebtToken =
+                FromAccountId::from_account_id(reserve.stable_debt_token_address);
+            let liquidity = self.reserve_liquidity(asset, &reserve)?;
+            if liquidity.utilization_rate < REBALANCE_UP_USAGE_RATIO_THRESHOLD
+                || stable_debt_token.get_user_stable_rate(user) >= reserve.current_liquidity_rate
+            {
+                return Err(LendingError::LpInterestRateRebalanceConditionsNotMet);
+            }
+
+            // burning the whole debt resets the rate of the user to the one of the new mint
+            let new_rate = reserve.current_stable_borrow_rate;
+            self.burn_stable_debt(&reserve, user, stable_debt)?;
+            // the debt is burnt from here on: returning an error would not roll the burn
+            // back, so any failure traps and reverts the whole call
+            revert_on_error(
+                "rebalance",
+                self.mint_stable_debt(&reserve, user, user, stable_debt, new_rate),
+            );
+            revert_on_error("rebalance", self.update_interest_rates(asset, &mut reserve));
+            self.reserves.insert(asset, reserve);
+
+            self.env().emit_event(RebalanceStableBorrowRate {
+                reserve: asset,
+                user,
+                new_rate,
+            });
+            Ok(())
         }
 
+        /**
+         * @dev Allows depositors to enable/disable a specific deposited asset as collateral
+         * - Disabling is only allowed if the rest of the collateral still covers the debt of the user
+         * @param asset The address of the underlying asset deposited
+         * @param use_as_collateral `true` if the user wants to use the deposit as collateral, `false` otherwise
+         **/
         #[ink(message)]
-        pub fn delegate_of(&self, delegatee: AccountId) -> Vec<(AccountId, Balance)> {
-            let mut delegates = vec![];
-            for v in self.delegate_allowance.iter() {
-                if v.0 .1 == delegatee {
-                    delegates.push((v.0 .0, *v.1))
+        pub fn set_user_use_reserve_as_collateral(
+            &mut self,
+            asset: AccountId,
+            use_as_collateral: bool,
+        ) -> Result<()> {
+            self.when_not_paused()?;
+            let user = self.env().caller();
+            let reserve = self.get_reserve(asset)?;
+            if !reserve.configuration.is_active() {
+                return Err(LendingError::VlNoActiveReserve);
+            }
+
+            let stoken: Stoken = FromAccountId::from_account_id(reserve.stoken_address);
+            let scaled_balance = stoken.scaled_balance_of(user);
+            if scaled_balance == 0 {
+                return Err(LendingError::VlCollateralBalanceIs0);
+            }
+            if !use_as_collateral {
+                let now = Self::env().block_timestamp();
+                let balance = ray_mul(scaled_balance, reserve.normalized_income(now)?)?;
+                if !self.balance_decrease_allowed(asset, user, balance)? {
+                    return Err(LendingError::VlDepositAlreadyInUse);
                 }
             }
-            delegates
+            self.set_user_collateral(asset, reserve.id, user, use_as_collateral);
+            Ok(())
+        }
+
+        /// Returns the reserves `user` borrows or uses as collateral.
+        #[ink(message)]
+        pub fn get_user_configuration(&self, user: AccountId) -> UserConfiguration {
+            self.get_user_config(user)
+        }
+
+        /**
+         * @dev Function to liquidate a non-healthy position collateral-wise, with Health Factor below 1
+         * - The caller (liquidator) covers `debt_to_cover` amount of debt of the user getting liquidated, and receives
+         *   a proportionally amount of the `collateral_asset` plus a bonus to cover market risk
+         * - The caller must have approved the pool to spend the covered amount of `debt_asset`
+         * @param collateral_asset The address of the underlying asset used as collateral, to receive as result of the liquidation
+         * @param debt_asset The address of the underlying borrowed asset to be repaid with the liquidation
+         * @param user The address of the borrower getting liquidated
+         * @param debt_to_cover The debt amount of borrowed `asset` the liquidator wants to cover, capped by the close factor
+         * @param receive_stoken `true` if the liquidators wants to receive the collateral sTokens, `false` if he wants
+         * to receive the underlying collateral asset directly
+         **/
+        #[ink(message)]
+        pub fn liquidation_call(
+            &mut self,
+            collateral_asset: AccountId,
+            debt_asset: AccountId,
+            user: AccountId,
+            debt_to_cover: Balance,
+            receive_stoken: bool,
+        ) -> Result<()> {
+            self.when_not_paused()?;
+            let liquidator = self.env().caller();
+            let now = Self::env().block_timestamp();
+            let mut collateral_reserve = self.get_reserve(collateral_asset)?;
+            let mut debt_reserve = self.get_reserve(debt_asset)?;
+            if !collateral_reserve.configuration.is_active()
+                || !debt_reserve.configuration.is_active()
+            {
+                return Err(LendingError::VlNoActiveReserve);
+            }
+            self.update_state(&mut collateral_reserve, now)?;
+            self.update_state(&mut debt_reserve, now)?;
+
+            let account = self.calculate_user_account_data(user)?;
+            if account.health_factor >= HEALTH_FACTOR_LIQUIDATION_THRESHOLD {
+                return Err(LendingError::LpcmHealthFactorNotBelowThreshold);
+            }
+
+            let mut collateral_stoken: Stoken =
+                FromAccountId::from_account_id(collateral_reserve.stoken_address);
+            let user_collateral = ray_mul(
+                collateral_stoken.scaled_balance_of(user),
+                collateral_reserve.liquidity_index,
+            )?;
+            let user_config = self.get_user_config(user);
+            if user_collateral == 0
+                || collateral_reserve.configuration.liquidation_threshold() == 0
+                || !user_config.is_using_as_collateral(collateral_reserve.id)
+            {
+                return Err(LendingError::LpcmCollateralCannotBeLiquidated);
+            }
+            let (_, user_stable_debt) = self.stable_debt_of(&debt_reserve, user)?;
+            let (_, user_variable_debt) = self.variable_debt_of(&debt_reserve, user, now)?;
+            let user_debt = user_stable_debt
+                .checked_add(user_variable_debt)
+                .ok_or(LendingError::MathAdditionOverflow)?;
+            if user_debt == 0 {
+                return Err(LendingError::LpcmSpecifiedCurrencyNotBorrowedByUser);
+            }
+
+            let max_liquidatable_debt = percent_mul(user_debt, LIQUIDATION_CLOSE_FACTOR_PERCENT)?;
+            let mut actual_debt_to_liquidate = if debt_to_cover > max_liquidatable_debt {
+                max_liquidatable_debt
+            } else {
+                debt_to_cover
+            };
+
+            // collateral worth the covered debt, plus the liquidation bonus
+            let debt_price = self.get_asset_price(debt_asset)?;
+            let collateral_price = self.get_asset_price(collateral_asset)?;
+            let collateral_decimals = collateral_reserve.configuration.decimals();
+            let debt_decimals = debt_reserve.configuration.decimals();
+            let liquidation_bonus = collateral_reserve.configuration.liquidation_bonus();
+            let debt_value =
+                to_base_currency(actual_debt_to_liquidate, debt_price, debt_decimals)?;
+            let mut collateral_amount = percent_mul(
+                from_base_currency(debt_value, collateral_price, collateral_decimals)?,
+                liquidation_bonus,
+            )?;
+            if collateral_amount > user_collateral {
+                collateral_amount = user_collateral;
+                let collateral_value =
+                    to_base_currency(user_collateral, collateral_price, collateral_decimals)?;
+                actual_debt_to_liquidate = from_base_currency(
+                    percent_div(collateral_value, liquidation_bonus)?,
+                    debt_price,
+                    debt_decimals,
+                )?;
+            }
+            if actual_debt_to_liquidate == 0 {
+                return Err(LendingError::VlInvalidAmount);
+            }
+
+            let mut collateral_underlying: Token = FromAccountId::from_account_id(collateral_asset);
+            if !receive_stoken
+                && collateral_amount > collateral_underlying.balance_of(self.env().account_id())
+            {
+                return Err(LendingError::LpcmNotEnoughLiquidityToLiquidate);
+            }
+
+            let mut debt_underlying: Token = FromAccountId::from_account_id(debt_asset);
+            debt_underlying.transfer_from(
+                liquidator,
+                self.env().account_id(),
+                actual_debt_to_liquidate,
+            )?;
+            // the payment is in the pool from here on: returning an error would not roll the
+            // transfer back, so any failure traps and reverts the whole call.
+            // The variable debt is repaid first, the stable debt only for the rest.
+            if user_variable_debt >= actual_debt_to_liquidate {
+                revert_on_error(
+                    "liquidation",
+                    self.burn_variable_debt(&debt_reserve, user, actual_debt_to_liquidate, now),
+                );
+            } else {
+                if user_variable_debt != 0 {
+                    revert_on_error(
+                        "liquidation",
+                        self.burn_variable_debt(&debt_reserve, user, user_variable_debt, now),
+                    );
+                }
+                revert_on_error(
+                    "liquidation",
+                    self.burn_stable_debt(
+                        &debt_reserve,
+                        user,
+                        actual_debt_to_liquidate - user_variable_debt,
+                    ),
+                );
+            }
+            if actual_debt_to_liquidate == user_debt {
+                self.set_user_borrowing(debt_reserve.id, user, false);
+            }
+
+            let collateral_index = collateral_reserve.liquidity_index;
+            if receive_stoken {
+                let is_first_deposit = collateral_stoken.scaled_balance_of(liquidator) == 0;
+                revert_on_error(
+                    "liquidation",
+                    collateral_stoken.transfer_on_liquidation(
+                        user,
+                        liquidator,
+                        collateral_amount,
+                        collateral_index,
+                    ),
+                );
+                if is_first_deposit {
+                    self.set_user_collateral(
+                        collateral_asset,
+                        collateral_reserve.id,
+                        liquidator,
+                        true,
+                    );
+                }
+            } else {
+                revert_on_error(
+                    "liquidation",
+                    collateral_stoken.burn(user, collateral_amount, collateral_index),
+                );
+                revert_on_error(
+                    "liquidation",
+                    collateral_underlying.transfer(liquidator, collateral_amount),
+                );
+            }
+            if collateral_amount == user_collateral {
+                self.set_user_collateral(collateral_asset, collateral_reserve.id, user, false);
+            }
+            revert_on_error(
+                "liquidation",
+                self.update_interest_rates(debt_asset, &mut debt_reserve),
+            );
+            self.reserves.insert(debt_asset, debt_reserve);
+            if collateral_asset != debt_asset {
+                revert_on_error(
+                    "liquidation",
+                    self.update_interest_rates(collateral_asset, &mut collateral_reserve),
+                );
+                self.reserves.insert(collateral_asset, collateral_reserve);
+            }
+
+            self.env().emit_event(LiquidationCall {
+                collateral_asset,
+                debt_asset,
+                user,
+                debt_to_cover: actual_debt_to_liquidate,
+                liquidated_collateral_amount: collateral_amount,
+                liquidator,
+                receive_stoken,
+            });
+            Ok(())
+        }
+
+        /**
+         * @dev Allows smart contracts to access the liquidity of the pool within one transaction,
+         * as long as the amount taken plus a premium is returned or debt is opened for it
+         * - The receiver must implement `FlashLoanReceiver`
+         * - For every asset in mode 0, the receiver must approve the pool to spend `amount + premium`
+         * @param receiver The address of the contract receiving the funds and running the operation
+         * @param assets The addresses of the assets being flash-borrowed
+         * @param amounts The amounts of the assets being flash-borrowed
+         * @param modes The debt to open for each asset instead of paying it back:
+         *   0 -> Don't open any debt, the amount plus the premium is pulled back from the receiver
+         *   1 -> Open debt at stable rate for the caller, who needs enough collateral or credit delegation
+         *   2 -> Open debt at variable rate for the caller, who needs enough collateral or credit delegation
+         * @param params Variadic packed params passed to the receiver as extra information
+         **/
+        #[ink(message)]
+        pub fn flash_loan(
+            &mut self,
+            receiver: AccountId,
+            assets: Vec<AccountId>,
+            amounts: Vec<Balance>,
+            modes: Vec<u8>,
+            params: Vec<u8>,
+        ) -> Result<()> {
+            self.when_not_paused()?;
+            if assets.is_empty() || assets.len() != amounts.len() || assets.len() != modes.len() {
+                return Err(LendingError::LpInconsistentFlashloanParams);
+            }
+
+            let pool = self.env().account_id();
+            let mut premiums = Vec::with_capacity(assets.len());
+            for ((asset, amount), mode) in assets.iter().zip(amounts.iter()).zip(modes.iter()) {
+                if *mode > 2 {
+                    return Err(LendingError::LpInvalidFlashloanMode);
+                }
+                let reserve = self.get_reserve(*asset)?;
+                if !reserve.configuration.is_active() {
+                    return Err(LendingError::VlNoActiveReserve);
+                }
+                if *mode != 0 && reserve.configuration.is_frozen() {
+                    return Err(LendingError::VlReserveFrozen);
+                }
+                if *mode != 0 && !reserve.configuration.borrowing_enabled() {
+                    return Err(LendingError::VlBorrowingNotEnabled);
+                }
+                if *mode == 1 && !reserve.configuration.stable_borrowing_enabled() {
+                    return Err(LendingError::VlStableBorrowingNotEnabled);
+                }
+                let premium = percent_mul(*amount, FLASHLOAN_PREMIUM_TOTAL)?;
+                if *mode == 0 && premium == 0 {
+                    return Err(LendingError::LpRequestedAmountTooSmall);
+                }
+                let underlying: Token = FromAccountId::from_account_id(*asset);
+                if *amount == 0 || *amount > underlying.balance_of(pool) {
+                    return Err(LendingError::VlCurrentAvailableLiquidityNotEnough);
+                }
+                premiums.push(premium);
+            }
+
+            // the funds leave the pool from here on: returning an error would not roll the
+            // transfers back, so any failure traps and reverts the whole call
+            if let Err(error) =
+                self.execute_flash_loan(receiver, assets, amounts, modes, premiums, params)
+            {
+                panic!("flash loan failed: {:?}", error);
+            }
+            Ok(())
+        }
+
+        /**
+         * @dev Mints to the treasury the sTokens accrued from the reserve factor share of the borrow interest
+         * - Anyone can call it, inactive reserves are skipped
+         * @param assets The addresses of the underlying assets of the reserves to mint for
+         **/
+        #[ink(message)]
+        pub fn mint_to_treasury(&mut self, assets: Vec<AccountId>) -> Result<()> {
+            let now = Self::env().block_timestamp();
+            for asset in assets {
+                let mut reserve = self.get_reserve(asset)?;
+                if !reserve.configuration.is_active() || reserve.accrued_to_treasury == 0 {
+                    continue;
+                }
+                let normalized_income = reserve.normalized_income(now)?;
+                let amount_to_mint = ray_mul(reserve.accrued_to_treasury, normalized_income)?;
+                let mut stoken: Stoken = FromAccountId::from_account_id(reserve.stoken_address);
+                stoken.mint(self.treasury, amount_to_mint, normalized_income)?;
+                reserve.accrued_to_treasury = 0;
+                self.reserves.insert(asset, reserve);
+
+                self.env().emit_event(MintedToTreasury {
+                    reserve: asset,
+                    amount_minted: amount_to_mint,
+                });
+            }
+            Ok(())
+        }
+
+        /**
+         * @dev Replaces the interest rate strategy of a reserve
+         * - Only callable by the risk admin or the pool admin
+         * - The interest is accrued at the old rates up to now, the new rates apply right away
+         * @param asset The address of the underlying asset of the reserve
+         * @param strategy The new interest rate strategy
+         **/
+        #[ink(message)]
+        pub fn set_reserve_interest_rate_strategy(
+            &mut self,
+            asset: AccountId,
+            strategy: DefaultInterestRateStrategy,
+        ) -> Result<()> {
+            self.only_risk_or_pool_admin()?;
+            strategy.validate()?;
+            let mut reserve = self.get_reserve(asset)?;
+            self.update_state(&mut reserve, Self::env().block_timestamp())?;
+            reserve.interest_rate_strategy = strategy;
+            self.update_interest_rates(asset, &mut reserve)?;
+            self.reserves.insert(asset, reserve);
+            Ok(())
+        }
+
+        /**
+         * @dev Configures a reserve as collateral
+         * - Only callable by the risk admin or the pool admin
+         * - A zero `liquidation_threshold` (with zero `ltv` and `liquidation_bonus`) disables the reserve as collateral
+         * @param asset The address of the underlying asset of the reserve
+         * @param ltv The loan to value of the asset when used as collateral, in basis points
+         * @param liquidation_threshold The threshold at which loans using this asset as collateral will be
+         * considered undercollateralized, in basis points
+         * @param liquidation_bonus The bonus liquidators receive to liquidate this asset, in basis points
+         **/
+        #[ink(message)]
+        pub fn configure_reserve_as_collateral(
+            &mut self,
+            asset: AccountId,
+            ltv: u128,
+            liquidation_threshold: u128,
+            liquidation_bonus: u128,
+        ) -> Result<()> {
+            self.only_risk_or_pool_admin()?;
+            let reserve = self
+                .reserves
+                .get_mut(&asset)
+                .ok_or(LendingError::VlNoActiveReserve)?;
+            let mut configuration = reserve.configuration;
+            configuration.set_collateral_params(ltv, liquidation_threshold, liquidation_bonus)?;
+            reserve.configuration = configuration;
+            Ok(())
+        }
+
+        /**
+         * @dev Caps the debt of a reserve, new borrows can't take it above the cap
+         * - Only callable by the risk admin or the pool admin
+         * @param asset The address of the underlying asset of the reserve
+         * @param borrow_cap The new borrow cap, in whole tokens, 0 to remove the cap
+         **/
+        #[ink(message)]
+        pub fn set_borrow_cap(&mut self, asset: AccountId, borrow_cap: u128) -> Result<()> {
+            self.only_risk_or_pool_admin()?;
+            let reserve = self
+                .reserves
+                .get_mut(&asset)
+                .ok_or(LendingError::VlNoActiveReserve)?;
+            reserve.configuration.set_borrow_cap(borrow_cap)
+        }
+
+        /**
+         * @dev Caps the sToken supply of a reserve, new deposits can't take it above the cap
+         * - Only callable by the risk admin or the pool admin
+         * @param asset The address of the underlying asset of the reserve
+         * @param supply_cap The new supply cap, in whole tokens, 0 to remove the cap
+         **/
+        #[ink(message)]
+        pub fn set_supply_cap(&mut self, asset: AccountId, supply_cap: u128) -> Result<()> {
+            self.only_risk_or_pool_admin()?;
+            let reserve = self
+                .reserves
+                .get_mut(&asset)
+                .ok_or(LendingError::VlNoActiveReserve)?;
+            reserve.configuration.set_supply_cap(supply_cap)
+        }
+
+        /**
+         * @dev Sets the share of the borrow interest of a reserve kept by the protocol
+         * - Only callable by the risk admin or the pool admin
+         * @param asset The address of the underlying asset of the reserve
+         * @param reserve_factor The new reserve factor, in basis points
+         **/
+        #[ink(message)]
+        pub fn set_reserve_factor(&mut self, asset: AccountId, reserve_factor: u128) -> Result<()> {
+            self.only_risk_or_pool_admin()?;
+            let reserve = self
+                .reserves
+                .get_mut(&asset)
+                .ok_or(LendingError::VlNoActiveReserve)?;
+            reserve.configuration.set_reserve_factor(reserve_factor)
+        }
+
+        /// Returns the packed risk parameters of a reserve.
+        #[ink(message)]
+        pub fn get_configuration(&self, asset: AccountId) -> Option<ReserveConfiguration> {
+            self.reserves.get(&asset).map(|reserve| reserve.configuration)
+        }
+
+        /**
+         * @dev Enables borrowing on a reserve
+         * - Only callable by the pool admin
+         * @param asset The address of the underlying asset of the reserve
+         * @param stable_borrow_rate_enabled `true` if stable borrow rate needs to be enabled by default on this reserve
+         **/
+        #[ink(message)]
+        pub fn enable_borrowing_on_reserve(
+            &mut self,
+            asset: AccountId,
+            stable_borrow_rate_enabled: bool,
+        ) -> Result<()> {
+            self.only_pool_admin()?;
+            let reserve = self
+                .reserves
+                .get_mut(&asset)
+                .ok_or(LendingError::VlNoActiveReserve)?;
+            reserve.configuration.set_borrowing_enabled(true);
+            reserve
+                .configuration
+                .set_stable_borrowing_enabled(stable_borrow_rate_enabled);
+            self.env().emit_event(BorrowingEnabledOnReserve {
+                asset,
+                stable_rate_enabled: stable_borrow_rate_enabled,
+            });
+            Ok(())
+        }
+
+        /**
+         * @dev Disables borrowing on a reserve
+         * - Only callable by the pool admin
+         * @param asset The address of the underlying asset of the reserve
+         **/
+        #[ink(message)]
+        pub fn disable_borrowing_on_reserve(&mut self, asset: AccountId) -> Result<()> {
+            self.only_pool_admin()?;
+            let reserve = self
+                .reserves
+                .get_mut(&asset)
+                .ok_or(LendingError::VlNoActiveReserve)?;
+            reserve.configuration.set_borrowing_enabled(false);
+            self.env().emit_event(BorrowingDisabledOnReserve { asset });
+            Ok(())
+        }
+
+        /**
+         * @dev Enables stable rate borrowing on a reserve
+         * - Only callable by the pool admin
+         * @param asset The address of the underlying asset of the reserve
+         **/
+        #[ink(message)]
+        pub fn enable_reserve_stable_rate(&mut self, asset: AccountId) -> Result<()> {
+            self.only_pool_admin()?;
+            let reserve = self
+                .reserves
+                .get_mut(&asset)
+                .ok_or(LendingError::VlNoActiveReserve)?;
+            reserve.configuration.set_stable_borrowing_enabled(true);
+            self.env().emit_event(StableRateEnabledOnReserve { asset });
+            Ok(())
+        }
+
+        /**
+         * @dev Disables stable rate borrowing on a reserve, existing stable debt keeps its rate
+         * - Only callable by the pool admin
+         * @param asset The address of the underlying asset of the reserve
+         **/
+        #[ink(message)]
+        pub fn disable_reserve_stable_rate(&mut self, asset: AccountId) -> Result<()> {
+            self.only_pool_admin()?;
+            let reserve = self
+                .reserves
+                .get_mut(&asset)
+                .ok_or(LendingError::VlNoActiveReserve)?;
+            reserve.configuration.set_stable_borrowing_enabled(false);
+            self.env().emit_event(StableRateDisabledOnReserve { asset });
+            Ok(())
+        }
+
+        /**
+         * @dev Activates a reserve
+         * - Only callable by the pool admin
+         * @param asset The address of the underlying asset of the reserve
+         **/
+        #[ink(message)]
+        pub fn activate_reserve(&mut self, asset: AccountId) -> Result<()> {
+            self.only_pool_admin()?;
+            let reserve = self
+                .reserves
+                .get_mut(&asset)
+                .ok_or(LendingError::VlNoActiveReserve)?;
+            reserve.configuration.set_active(true);
+            self.env().emit_event(ReserveActivated { asset });
+            Ok(())
+        }
+
+        /**
+         * @dev Deactivates a reserve, which must hold no more liquidity
+         * - Only callable by the pool admin
+         * @param asset The address of the underlying asset of the reserve
+         **/
+        #[ink(message)]
+        pub fn deactivate_reserve(&mut self, asset: AccountId) -> Result<()> {
+            self.only_pool_admin()?;
+            let mut reserve = self.get_reserve(asset)?;
+            let underlying: Token = FromAccountId::from_account_id(asset);
+            if underlying.balance_of(self.env().account_id()) != 0
+                || reserve.current_liquidity_rate != 0
+            {
+                return Err(LendingError::LpcReserveLiquidityNot0);
+            }
+            reserve.configuration.set_active(false);
+            self.reserves.insert(asset, reserve);
+            self.env().emit_event(ReserveDeactivated { asset });
+            Ok(())
+        }
+
+        /**
+         * @dev Freezes a reserve: no new deposits or borrows, but repays, withdrawals and
+         * liquidations are still allowed
+         * - Only callable by the risk admin or the pool admin
+         * @param asset The address of the underlying asset of the reserve
+         **/
+        #[ink(message)]
+        pub fn freeze_reserve(&mut self, asset: AccountId) -> Result<()> {
+            self.only_risk_or_pool_admin()?;
+            let reserve = self
+                .reserves
+                .get_mut(&asset)
+                .ok_or(LendingError::VlNoActiveReserve)?;
+            reserve.configuration.set_frozen(true);
+            self.env().emit_event(ReserveFrozen { asset });
+            Ok(())
+        }
+
+        /**
+         * @dev Unfreezes a reserve
+         * - Only callable by the risk admin or the pool admin
+         * @param asset The address of the underlying asset of the reserve
+         **/
+        #[ink(message)]
+        pub fn unfreeze_reserve(&mut self, asset: AccountId) -> Result<()> {
+            self.only_risk_or_pool_admin()?;
+            let reserve = self
+                .reserves
+                .get_mut(&asset)
+                .ok_or(LendingError::VlNoActiveReserve)?;
+            reserve.configuration.set_frozen(false);
+            self.env().emit_event(ReserveUnfrozen { asset });
+            Ok(())
+        }
+
+        /**
+         * @dev Pauses or unpauses every action on the reserves of the pool
+         * - Only callable by the emergency admin
+         * @param paused `true` to pause the pool, `false` to unpause it
+         **/
+        #[ink(message)]
+        pub fn set_pause(&mut self, paused: bool) -> Result<()> {
+            self.only_emergency_admin()?;
+            self.paused = paused;
+            let emergency_admin = self.env().caller();
+            if paused {
+                self.env().emit_event(Paused { emergency_admin });
+            } else {
+                self.env().emit_event(Unpaused { emergency_admin });
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn paused(&self) -> bool {
+            self.paused
+        }
+
+        /**
+         * @dev Sets the oracle pricing the reserves in the base currency
+         * - Only callable by the pool admin
+         * @param oracle The address of a contract exposing `get_asset_price`
+         **/
+        #[ink(message)]
+        pub fn set_price_oracle(&mut self, oracle: AccountId) -> Result<()> {
+            self.only_pool_admin()?;
+            self.price_oracle = oracle;
+            Ok(())
+        }
+
+        /**
+         * @dev Sets the oracle queried when the price oracle has no fresh price for an asset
+         * - Only callable by the pool admin
+         * @param oracle The address of a contract exposing `get_asset_price`, `None` to disable the fallback
+         **/
+        #[ink(message)]
+        pub fn set_fallback_oracle(&mut self, oracle: Option<AccountId>) -> Result<()> {
+            self.only_pool_admin()?;
+            self.fallback_oracle = oracle;
+            Ok(())
+        }
+
+        /**
+         * @dev Sets the age after which an oracle price is considered stale
+         * - Only callable by the pool admin
+         * @param max_price_age The maximum age of a price, in milliseconds
+         **/
+        #[ink(message)]
+        pub fn set_max_price_age(&mut self, max_price_age: u64) -> Result<()> {
+            self.only_pool_admin()?;
+            self.max_price_age = max_price_age;
+            Ok(())
+        }
+
+        /**
+         * @dev Sets the account receiving the sTokens minted by mint_to_treasury(), e.g. a collector contract
+         * - Only callable by the pool admin
+         * @param treasury The address of the new treasury
+         **/
+        #[ink(message)]
+        pub fn set_treasury(&mut self, treasury: AccountId) -> Result<()> {
+            self.only_pool_admin()?;
+            self.treasury = treasury;
+            Ok(())
+        }
+
+        /// Returns the account receiving the sTokens minted by `mint_to_treasury`.
+        #[ink(message)]
+        pub fn get_treasury(&self) -> AccountId {
+            self.treasury
+        }
+
+        /// Returns the price oracle, the fallback oracle and the maximum price age.
+        #[ink(message)]
+        pub fn get_price_oracle(&self) -> (AccountId, Option<AccountId>, u64) {
+            (self.price_oracle, self.fallback_oracle, self.max_price_age)
+        }
+
+        /**
+         * @dev Hands a configuration role to another account
+         * - Only callable by the pool admin, who can hand over its own role too
+         * @param role The role to transfer
+         * @param account The account that will hold the role
+         **/
+        #[ink(message)]
+        pub fn set_role(&mut self, role: Role, account: AccountId) -> Result<()> {
+            self.only_pool_admin()?;
+            let holder = match role {
+                Role::PoolAdmin => &mut self.pool_admin,
+                Role::RiskAdmin => &mut self.risk_admin,
+                Role::EmergencyAdmin => &mut self.emergency_admin,
+            };
+            let previous_account = core::mem::replace(holder, account);
+            self.env().emit_event(RoleTransferred {
+                role,
+                previous_account,
+                new_account: account,
+            });
+            Ok(())
+        }
+
+        /// Returns the account holding `role`.
+        #[ink(message)]
+        pub fn get_role(&self, role: Role) -> AccountId {
+            match role {
+                Role::PoolAdmin => self.pool_admin,
+                Role::RiskAdmin => self.risk_admin,
+                Role::EmergencyAdmin => self.emergency_admin,
+            }
+        }
+
+        /// Returns the list of the initialized reserves, by underlying asset.
+        #[ink(message)]
+        pub fn get_reserves_list(&self) -> Vec<AccountId> {
+            self.reserves_list.iter().copied().collect()
+        }
+
+        fn only_pool_admin(&self) -> Result<()> {
+            if self.env().caller() != self.pool_admin {
+                return Err(LendingError::LpCallerNotLendingPoolConfigurator);
+            }
+            Ok(())
+        }
+
+        fn only_risk_or_pool_admin(&self) -> Result<()> {
+            let caller = self.env().caller();
+            if caller != self.risk_admin && caller != self.pool_admin {
+                return Err(LendingError::LpCallerNotLendingPoolConfigurator);
+            }
+            Ok(())
+        }
+
+        fn only_emergency_admin(&self) -> Result<()> {
+            if self.env().caller() != self.emergency_admin {
+                return Err(LendingError::LpcCallerNotEmergencyAdmin);
+            }
+            Ok(())
+        }
+
+        fn when_not_paused(&self) -> Result<()> {
+            if self.paused {
+                return Err(LendingError::LpIsPaused);
+            }
+            Ok(())
+        }
+
+        fn get_reserve(&self, asset: AccountId) -> Result<ReserveData> {
+            self.reserves
+                .get(&asset)
+                .cloned()
+                .ok_or(LendingError::VlNoActiveReserve)
+        }
+
+        fn get_user_config(&self, user: AccountId) -> UserConfiguration {
+            self.users_config.get(&user).copied().unwrap_or_default()
+        }
+
+        /// Records whether the `asset` deposit of `user` backs their debt, emitting the matching
+        /// event when it changes.
+        fn set_user_collateral(
+            &mut self,
+            asset: AccountId,
+            reserve_id: u8,
+            user: AccountId,
+            use_as_collateral: bool,
+        ) {
+            let mut user_config = self.get_user_config(user);
+            if user_config.is_using_as_collateral(reserve_id) == use_as_collateral {
+                return;
+            }
+            user_config.set_using_as_collateral(reserve_id, use_as_collateral);
+            self.users_config.insert(user, user_config);
+            if use_as_collateral {
+                self.env().emit_event(ReserveUsedAsCollateralEnabled {
+                    reserve: asset,
+                    user,
+                });
+            } else {
+                self.env().emit_event(ReserveUsedAsCollateralDisabled {
+                    reserve: asset,
+                    user,
+                });
+            }
+        }
+
+        /// Records whether `user` has debt in the reserve.
+        fn set_user_borrowing(&mut self, reserve_id: u8, user: AccountId, borrowing: bool) {
+            let mut user_config = self.get_user_config(user);
+            user_config.set_borrowing(reserve_id, borrowing);
+            self.users_config.insert(user, user_config);
+        }
+
+        /// Price of one whole `asset` token in the base currency, from the first oracle with a fresh price.
+        fn get_asset_price(&self, asset: AccountId) -> Result<u128> {
+            let oracle: PriceOracle = FromAccountId::from_account_id(self.price_oracle);
+            if let Some(price) = self.fresh_price(&oracle, asset) {
+                return Ok(price);
+            }
+            if let Some(fallback) = self.fallback_oracle {
+                let oracle: PriceOracle = FromAccountId::from_account_id(fallback);
+                if let Some(price) = self.fresh_price(&oracle, asset) {
+                    return Ok(price);
+                }
+            }
+            Err(LendingError::LpAssetPriceUnavailable)
+        }
+
+        fn fresh_price(&self, oracle: &PriceOracle, asset: AccountId) -> Option<u128> {
+            let (price, updated_at) = oracle.get_asset_price(asset)?;
+            let age = Self::env().block_timestamp().saturating_sub(updated_at);
+            if price == 0 || age > self.max_price_age {
+                return None;
+            }
+            Some(price)
+        }
+
+        /// Accrues the interest of the reserve up to `now`, setting aside for the treasury the
+        /// reserve factor share of the interest the borrowers accrued since the last update.
+        fn update_state(&self, reserve: &mut ReserveData, now: u64) -> Result<()> {
+            let previous_update_timestamp = reserve.last_update_timestamp;
+            let previous_variable_borrow_index = reserve.variable_borrow_index;
+            reserve.update_state(now)?;
+            let reserve_factor = reserve.configuration.reserve_factor();
+            if previous_update_timestamp == now || reserve_factor == 0 {
+                return Ok(());
+            }
+
+            let variable_debt_token: VariableDebtToken =
+                FromAccountId::from_account_id(reserve.variable_debt_token_address);
+            let scaled_variable_debt = variable_debt_token.scaled_total_supply();
+            let previous_variable_debt =
+                ray_mul(scaled_variable_debt, previous_variable_borrow_index)?;
+            let current_variable_debt =
+                ray_mul(scaled_variable_debt, reserve.variable_borrow_index)?;
+
+            let stable_debt_token: StableDebtToken =
+                FromAccountId::from_account_id(reserve.stable_debt_token_address);
+            let (principal_stable_debt, current_stable_debt, average_stable_rate, stable_updated) =
+                stable_debt_token.get_supply_data();
+            let previous_stable_debt = ray_mul(
+                principal_stable_debt,
+                calculate_compounded_interest(
+                    average_stable_rate,
+                    stable_updated,
+                    previous_update_timestamp,
+                )?,
+            )?;
+
+            let debt_accrued = current_variable_debt
+                .saturating_add(current_stable_debt)
+                .saturating_sub(previous_variable_debt.saturating_add(previous_stable_debt));
+            let amount_to_mint = percent_mul(debt_accrued, reserve_factor)?;
+            if amount_to_mint != 0 {
+                reserve.accrued_to_treasury = reserve
+                    .accrued_to_treasury
+                    .checked_add(ray_div(amount_to_mint, reserve.liquidity_index)?)
+                    .ok_or(LendingError::MathAdditionOverflow)?;
+            }
+            Ok(())
+        }
+
+        /// Recomputes the rates of the reserve from its liquidity after the movements of an action.
+        fn update_interest_rates(&self, asset: AccountId, reserve: &mut ReserveData) -> Result<()> {
+            let liquidity = self.reserve_liquidity(asset, reserve)?;
+            let (liquidity_rate, stable_borrow_rate, variable_borrow_rate) =
+                reserve.interest_rate_strategy.calculate_interest_rates(
+                    liquidity.available_liquidity,
+                    liquidity.total_stable_debt,
+                    liquidity.total_variable_debt,
+                    liquidity.average_stable_rate,
+                    reserve.configuration.reserve_factor(),
+                )?;
+            reserve.current_liquidity_rate = liquidity_rate;
+            reserve.current_stable_borrow_rate = stable_borrow_rate;
+            reserve.current_variable_borrow_rate = variable_borrow_rate;
+
+            self.env().emit_event(ReserveDataUpdated {
+                reserve: asset,
+                liquidity_rate,
+                stable_borrow_rate,
+                variable_borrow_rate,
+                liquidity_index: reserve.liquidity_index,
+                variable_borrow_index: reserve.variable_borrow_index,
+            });
+            Ok(())
+        }
+
+        /// Reads the liquidity of the reserve and its debt at the variable borrow index of `reserve`,
+        /// which must be up to date.
+        fn reserve_liquidity(
+            &self,
+            asset: AccountId,
+            reserve: &ReserveData,
+        ) -> Result<ReserveLiquidityData> {
+            let underlying: Token = FromAccountId::from_account_id(asset);
+            let stable_debt_token: StableDebtToken =
+                FromAccountId::from_account_id(reserve.stable_debt_token_address);
+            let variable_debt_token: VariableDebtToken =
+                FromAccountId::from_account_id(reserve.variable_debt_token_address);
+
+            let available_liquidity = underlying.balance_of(self.env().account_id());
+            let (total_stable_debt, average_stable_rate) =
+                stable_debt_token.get_total_supply_and_avg_rate();
+            let total_variable_debt = ray_mul(
+                variable_debt_token.scaled_total_supply(),
+                reserve.variable_borrow_index,
+            )?;
+            let total_debt = total_stable_debt
+                .checked_add(total_variable_debt)
+                .ok_or(LendingError::MathAdditionOverflow)?;
+            let utilization_rate = if total_debt == 0 {
+                0
+            } else {
+                ray_div(
+                    total_debt,
+                    available_liquidity
+                        .checked_add(total_debt)
+                        .ok_or(LendingError::MathAdditionOverflow)?,
+                )?
+            };
+
+            Ok(ReserveLiquidityData {
+                available_liquidity,
+                total_stable_debt,
+                total_variable_debt,
+                average_stable_rate,
+                utilization_rate,
+            })
+        }
+
+        /// Values the collateral and debt of `user` in the base currency, interest included,
+        /// over the reserves their configuration marks as borrowed or used as collateral.
+        fn calculate_user_account_data(&self, user: AccountId) -> Result<UserAccountData> {
+            let now = Self::env().block_timestamp();
+            let user_config = self.get_user_config(user);
+            if user_config.is_empty() {
+                return Ok(UserAccountData {
+                    health_factor: u128::MAX,
+                    ..Default::default()
+                });
+            }
+            let mut total_collateral: Balance = 0;
+            let mut total_debt: Balance = 0;
+            let mut weighted_ltv: u128 = 0;
+            let mut weighted_threshold: u128 = 0;
+
+            for asset in self.reserves_list.iter() {
+                let reserve = self.get_reserve(*asset)?;
+                if !user_config.is_using_as_collateral_or_borrowing(reserve.id) {
+                    continue;
+                }
+                let price = self.get_asset_price(*asset)?;
+                let (ltv, liquidation_threshold, _, decimals, _) =
+                    reserve.configuration.get_params();
+
+                if liquidation_threshold != 0 && user_config.is_using_as_collateral(reserve.id) {
+                    let stoken: Stoken = FromAccountId::from_account_id(reserve.stoken_address);
+                    let collateral = to_base_currency(
+                        ray_mul(
+                            stoken.scaled_balance_of(user),
+                            reserve.normalized_income(now)?,
+                        )?,
+                        price,
+                        decimals,
+                    )?;
+                    total_collateral = total_collateral
+                        .checked_add(collateral)
+                        .ok_or(LendingError::MathAdditionOverflow)?;
+                    weighted_ltv = weighted_ltv
+                        .checked_add(
+                            collateral
+                                .checked_mul(ltv)
+                                .ok_or(LendingError::MathMultiplicationOverflow)?,
+                        )
+                        .ok_or(LendingError::MathAdditionOverflow)?;
+                    weighted_threshold = weighted_threshold
+                        .checked_add(
+                            collateral
+                                .checked_mul(liquidation_threshold)
+                                .ok_or(LendingError::MathMultiplicationOverflow)?,
+                        )
+                        .ok_or(LendingError::MathAdditionOverflow)?;
+                }
+                if user_config.is_borrowing(reserve.id) {
+                    let (_, stable_debt) = self.stable_debt_of(&reserve, user)?;
+                    let (_, variable_debt) = self.variable_debt_of(&reserve, user, now)?;
+                    let debt = to_base_currency(
+                        stable_debt
+                            .checked_add(variable_debt)
+                            .ok_or(LendingError::MathAdditionOverflow)?,
+                        price,
+                        decimals,
+                    )?;
+                    total_debt = total_debt
+                        .checked_add(debt)
+                        .ok_or(LendingError::MathAdditionOverflow)?;
+                }
+            }
+
+            let (ltv, current_liquidation_threshold) = if total_collateral != 0 {
+                (
+                    weighted_ltv / total_collateral,
+                    weighted_threshold / total_collateral,
+                )
+            } else {
+                (0, 0)
+            };
+            let health_factor = if total_debt != 0 {
+                wad_div(
+                    percent_mul(total_collateral, current_liquidation_threshold)?,
+                    total_debt,
+                )?
+            } else {
+                u128::MAX
+            };
+            let available_borrows = percent_mul(total_collateral, ltv)?.saturating_sub(total_debt);
+
+            Ok(UserAccountData {
+                total_collateral,
+                total_debt,
+                available_borrows,
+                current_liquidation_threshold,
+                ltv,
+                health_factor,
+            })
+        }
+
+        /// Whether `user` stays above the liquidation threshold once `amount` of his `asset`
+        /// deposit no longer backs his debt.
+        fn balance_decrease_allowed(
+            &self,
+            asset: AccountId,
+            user: AccountId,
+            amount: Balance,
+        ) -> Result<bool> {
+            let reserve = self.get_reserve(asset)?;
+            let user_config = self.get_user_config(user);
+            if !user_config.is_borrowing_any() || !user_config.is_using_as_collateral(reserve.id) {
+                return Ok(true);
+            }
+            let liquidation_threshold = reserve.configuration.liquidation_threshold();
+            if liquidation_threshold == 0 {
+                return Ok(true);
+            }
+            let account = self.calculate_user_account_data(user)?;
+            if account.total_debt == 0 {
+                return Ok(true);
+            }
+
+            let amount_value = to_base_currency(
+                amount,
+                self.get_asset_price(asset)?,
+                reserve.configuration.decimals(),
+            )?;
+            let adjusted_collateral_after =
+                percent_mul(account.total_collateral, account.current_liquidation_threshold)?
+                    .saturating_sub(percent_mul(amount_value, liquidation_threshold)?);
+            let health_factor_after = wad_div(adjusted_collateral_after, account.total_debt)?;
+            Ok(health_factor_after >= HEALTH_FACTOR_LIQUIDATION_THRESHOLD)
+        }
+
+        /// Opens `amount` of debt for `on_behalf_of`, sending the borrowed funds to `user` unless
+        /// they already left the pool, as with flash loans.
+        fn execute_borrow(
+            &mut self,
+            asset: AccountId,
+            user: AccountId,
+            on_behalf_of: AccountId,
+            amount: Balance,
+            rate_mode: InterestRateMode,
+            release_underlying: bool,
+        ) -> Result<()> {
+            if amount == 0 {
+                return Err(LendingError::VlInvalidAmount);
+            }
+
+            let sender = user;
+            let receiver = on_behalf_of;
+            let mut reserve = self.get_reserve(asset)?;
+            if !reserve.configuration.is_active() {
+                return Err(LendingError::VlNoActiveReserve);
+            }
+            if reserve.configuration.is_frozen() {
+                return Err(LendingError::VlReserveFrozen);
+            }
+            if !reserve.configuration.borrowing_enabled() {
+                return Err(LendingError::VlBorrowingNotEnabled);
+            }
+            let now = Self::env().block_timestamp();
+            self.update_state(&mut reserve, now)?;
+
+            let mut underlying: Token = FromAccountId::from_account_id(asset);
+
+            // borrowing on behalf of someone else takes credit they delegated on the debt token
+            if sender != receiver {
+                let borrow_allowance = match rate_mode {
+                    InterestRateMode::Stable => {
+                        let stable_debt_token: StableDebtToken =
+                            FromAccountId::from_account_id(reserve.stable_debt_token_address);
+                        stable_debt_token.borrow_allowance(receiver, sender)
+                    }
+                    InterestRateMode::Variable => {
+                        let variable_debt_token: VariableDebtToken =
+                            FromAccountId::from_account_id(reserve.variable_debt_token_address);
+                        variable_debt_token.borrow_allowance(receiver, sender)
+                    }
+                };
+                if amount > borrow_allowance {
+                    return Err(LendingError::CtBorrowAllowanceNotEnough);
+                }
+            }
+
+            // collateral * ltv - debt of all the reserves, valued in the base currency
+            let account = self.calculate_user_account_data(receiver)?;
+            if account.total_collateral == 0 {
+                return Err(LendingError::VlCollateralBalanceIs0);
+            }
+            if account.health_factor < HEALTH_FACTOR_LIQUIDATION_THRESHOLD {
+                return Err(LendingError::VlHealthFactorLowerThanLiquidationThreshold);
+            }
+            let amount_value = to_base_currency(
+                amount,
+                self.get_asset_price(asset)?,
+                reserve.configuration.decimals(),
+            )?;
+            if amount_value > account.available_borrows {
+                return Err(LendingError::VlCollateralCannotCoverNewBorrow);
+            }
+            let available_liquidity = underlying.balance_of(self.env().account_id());
+            if release_underlying && amount > available_liquidity {
+                return Err(LendingError::VlCurrentAvailableLiquidityNotEnough);
+            }
+            if rate_mode == InterestRateMode::Stable {
+                self.validate_stable_borrow(&reserve, receiver, amount, now)?;
+                // a single stable borrow can't take most of the liquidity at a low rate
+                let max_loan_size =
+                    percent_mul(available_liquidity, MAX_STABLE_RATE_BORROW_SIZE_PERCENT)?;
+                if release_underlying && amount > max_loan_size {
+                    return Err(LendingError::VlAmountBiggerThanMaxLoanSizeStable);
+                }
+            } else if ray_div(amount, reserve.variable_borrow_index)? == 0 {
+                return Err(LendingError::CtInvalidMintAmount);
+            }
+            if reserve.configuration.borrow_cap() != 0 {
+                let liquidity = self.reserve_liquidity(asset, &reserve)?;
+                let total_debt = liquidity
+                    .total_stable_debt
+                    .checked_add(liquidity.total_variable_debt)
+                    .and_then(|debt| debt.checked_add(amount))
+                    .ok_or(LendingError::MathAdditionOverflow)?;
+                if reserve.configuration.exceeds_borrow_cap(total_debt) {
+                    return Err(LendingError::VlBorrowCapExceeded);
+                }
+            }
+
+            // mint debt token to receiver, the token decreasing the delegated credit
+            let borrow_rate = match rate_mode {
+                InterestRateMode::Stable => {
+                    let rate = reserve.current_stable_borrow_rate;
+                    self.mint_stable_debt(&reserve, sender, receiver, amount, rate)?;
+                    rate
+                }
+                InterestRateMode::Variable => {
+                    self.mint_variable_debt(&reserve, sender, receiver, amount)?;
+                    reserve.current_variable_borrow_rate
+                }
+            };
+            self.set_user_borrowing(reserve.id, receiver, true);
+
+            // the debt is minted from here on: returning an error would not roll the mint
+            // back, so any failure traps and reverts the whole call
+            if release_underlying {
+                revert_on_error("borrow", underlying.transfer(sender, amount));
+            }
+            revert_on_error("borrow", self.update_interest_rates(asset, &mut reserve));
+            self.reserves.insert(asset, reserve);
+
+            self.env().emit_event(Borrow {
+                reserve: asset,
+                user: sender,
+                on_behalf_of,
+                amount,
+                borrow_rate_mode: rate_mode,
+                borrow_rate,
+            });
+            Ok(())
+        }
+
+        /// Checks that `user` can take `amount` of stable debt on the reserve: stable borrowing
+        /// must be enabled, and they can't borrow at a stable rate against a deposit of the same
+        /// asset used as collateral, which would let them earn more than they pay.
+        fn validate_stable_borrow(
+            &self,
+            reserve: &ReserveData,
+            user: AccountId,
+            amount: Balance,
+            now: u64,
+        ) -> Result<()> {
+            if !reserve.configuration.stable_borrowing_enabled() {
+                return Err(LendingError::VlStableBorrowingNotEnabled);
+            }
+            if !self.get_user_config(user).is_using_as_collateral(reserve.id)
+                || reserve.configuration.ltv() == 0
+            {
+                return Ok(());
+            }
+            let stoken: Stoken = FromAccountId::from_account_id(reserve.stoken_address);
+            let collateral = ray_mul(
+                stoken.scaled_balance_of(user),
+                reserve.normalized_income(now)?,
+            )?;
+            if collateral != 0 && amount <= collateral {
+                return Err(LendingError::VlCollateralSameAsBorrowingCurrency);
+            }
+            Ok(())
+        }
+
+        /// Returns the `(principal, current)` stable debt of `user`, the principal being their
+        /// debt as of their last action and the current debt including the interest accrued on
+        /// it at their stable rate since.
+        fn stable_debt_of(
+            &self,
+            reserve: &ReserveData,
+            user: AccountId,
+        ) -> Result<(Balance, Balance)> {
+            let stable_debt_token: StableDebtToken =
+                FromAccountId::from_account_id(reserve.stable_debt_token_address);
+            let principal = stable_debt_token.principal_balance_of(user);
+            if principal == 0 {
+                return Ok((0, 0));
+            }
+            Ok((principal, stable_debt_token.balance_of(user)))
+        }
+
+        /// Returns the `(scaled, current)` variable debt of `user`.
+        fn variable_debt_of(
+            &self,
+            reserve: &ReserveData,
+            user: AccountId,
+            now: u64,
+        ) -> Result<(Balance, Balance)> {
+            let variable_debt_token: VariableDebtToken =
+                FromAccountId::from_account_id(reserve.variable_debt_token_address);
+            let scaled = variable_debt_token.scaled_balance_of(user);
+            Ok((scaled, ray_mul(scaled, reserve.normalized_variable_debt(now)?)?))
+        }
+
+        /// Adds `amount` borrowed by `user` at `rate` to the stable debt of `on_behalf_of`, the
+        /// token averaging their rate with the new one.
+        fn mint_stable_debt(
+            &self,
+            reserve: &ReserveData,
+            user: AccountId,
+            on_behalf_of: AccountId,
+            amount: Balance,
+            rate: u128,
+        ) -> Result<()> {
+            let mut stable_debt_token: StableDebtToken =
+                FromAccountId::from_account_id(reserve.stable_debt_token_address);
+            stable_debt_token.mint(user, on_behalf_of, amount, rate)?;
+            Ok(())
+        }
+
+        /// Removes `amount` from the stable debt of `user`, the whole debt if it covers it.
+        fn burn_stable_debt(
+            &self,
+            reserve: &ReserveData,
+            user: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            let (_, current) = self.stable_debt_of(reserve, user)?;
+            let amount = if amount > current { current } else { amount };
+            let mut stable_debt_token: StableDebtToken =
+                FromAccountId::from_account_id(reserve.stable_debt_token_address);
+            stable_debt_token.burn(user, amount)?;
+            Ok(())
+        }
+
+        /// Adds `amount` borrowed by `user` to the variable debt of `on_behalf_of`, at the
+        /// current variable borrow index.
+        fn mint_variable_debt(
+            &self,
+            reserve: &ReserveData,
+            user: AccountId,
+            on_behalf_of: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            let mut variable_debt_token: VariableDebtToken =
+                FromAccountId::from_account_id(reserve.variable_debt_token_address);
+            variable_debt_token.mint(user, on_behalf_of, amount, reserve.variable_borrow_index)?;
+            Ok(())
+        }
+
+        /// Removes `amount` from the variable debt of `user`, the whole debt if it covers it.
+        fn burn_variable_debt(
+            &self,
+            reserve: &ReserveData,
+            user: AccountId,
+            amount: Balance,
+            now: u64,
+        ) -> Result<()> {
+            let (_, current) = self.variable_debt_of(reserve, user, now)?;
+            let amount = if amount > current { current } else { amount };
+            let mut variable_debt_token: VariableDebtToken =
+                FromAccountId::from_account_id(reserve.variable_debt_token_address);
+            variable_debt_token.burn(user, amount, reserve.variable_borrow_index)?;
+            Ok(())
+        }
+
+        /// Lends `amounts` of `assets` to `receiver`, runs its operation and then either pulls
+        /// back each amount plus its premium, or opens debt for the caller.
+        fn execute_flash_loan(
+            &mut self,
+            receiver: AccountId,
+            assets: Vec<AccountId>,
+            amounts: Vec<Balance>,
+            modes: Vec<u8>,
+            premiums: Vec<Balance>,
+            params: Vec<u8>,
+        ) -> Result<()> {
+            let initiator = self.env().caller();
+            let pool = self.env().account_id();
+
+            for (asset, amount) in assets.iter().zip(amounts.iter()) {
+                let mut underlying: Token = FromAccountId::from_account_id(*asset);
+                underlying.transfer(receiver, *amount)?;
+            }
+
+            let mut executor: Callee = FromAccountId::from_account_id(receiver);
+            if !executor.execute_operation(
+                assets.clone(),
+                amounts.clone(),
+                premiums.clone(),
+                initiator,
+                params,
+            ) {
+                return Err(LendingError::LpInvalidFlashLoanExecutorReturn);
+            }
+
+            for (i, asset) in assets.into_iter().enumerate() {
+                let (amount, premium) = (amounts[i], premiums[i]);
+                if modes[i] != 0 {
+                    // the receiver keeps the funds, the caller takes the debt
+                    let rate_mode = if modes[i] == 1 {
+                        InterestRateMode::Stable
+                    } else {
+                        InterestRateMode::Variable
+                    };
+                    self.execute_borrow(asset, initiator, initiator, amount, rate_mode, false)?;
+                    self.env().emit_event(FlashLoan {
+                        target: receiver,
+                        initiator,
+                        asset,
+                        amount,
+                        premium: 0,
+                    });
+                    continue;
+                }
+
+                let mut reserve = self.get_reserve(asset)?;
+                self.update_state(&mut reserve, Self::env().block_timestamp())?;
+                let mut underlying: Token = FromAccountId::from_account_id(asset);
+                let stoken: Stoken = FromAccountId::from_account_id(reserve.stoken_address);
+
+                // the premium goes to the depositors
+                let total_liquidity =
+                    ray_mul(stoken.scaled_total_supply(), reserve.liquidity_index)?;
+                reserve.cumulate_to_liquidity_index(total_liquidity, premium)?;
+                let amount_plus_premium = amount
+                    .checked_add(premium)
+                    .ok_or(LendingError::MathAdditionOverflow)?;
+                underlying.transfer_from(receiver, pool, amount_plus_premium)?;
+                self.update_interest_rates(asset, &mut reserve)?;
+                self.reserves.insert(asset, reserve);
+
+                self.env().emit_event(FlashLoan {
+                    target: receiver,
+                    initiator,
+                    asset,
+                    amount,
+                    premium,
+                });
+            }
+            Ok(())
+        }
+    }
+
+    impl PoolCallbacks for Lendingpool {
+        /// Returns the liquidity index of the reserve at the current block, by which sToken scaled
+        /// balances are multiplied, or `0` if `asset` is not listed.
+        #[ink(message)]
+        fn get_reserve_normalized_income(&self, asset: AccountId) -> u128 {
+            let now = Self::env().block_timestamp();
+            self.reserves
+                .get(&asset)
+                .and_then(|reserve| reserve.normalized_income(now).ok())
+                .unwrap_or(0)
+        }
+
+        /// Returns the variable borrow index of the reserve at the current block, by which
+        /// variable debt token scaled balances are multiplied, or `0` if `asset` is not listed.
+        #[ink(message)]
+        fn get_reserve_normalized_variable_debt(&self, asset: AccountId) -> u128 {
+            let now = Self::env().block_timestamp();
+            self.reserves
+                .get(&asset)
+                .and_then(|reserve| reserve.normalized_variable_debt(now).ok())
+                .unwrap_or(0)
+        }
+
+        /**
+         * @dev Validates and finalizes an sToken transfer
+         * - Only callable by the sToken of the `asset` reserve, before it moves the balances
+         * @param asset The address of the underlying asset of the sToken
+         * @param from The user from which the sTokens are transferred
+         * @param to The user receiving the sTokens
+         * @param amount The amount being transferred, in underlying units
+         * @param balance_from_before The sToken balance of the `from` user before the transfer
+         * @param balance_to_before The sToken balance of the `to` user before the transfer
+         **/
+        #[ink(message)]
+        fn finalize_transfer(
+            &mut self,
+            asset: AccountId,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+            balance_from_before: Balance,
+            balance_to_before: Balance,
+        ) -> Result<()> {
+            self.when_not_paused()?;
+            let reserve = self.get_reserve(asset)?;
+            if self.env().caller() != reserve.stoken_address {
+                return Err(LendingError::LpCallerMustBeAnAtoken);
+            }
+            if from == to || amount == 0 {
+                return Ok(());
+            }
+
+            // the collateral left to the sender must still cover their debt
+            if !self.balance_decrease_allowed(asset, from, amount)? {
+                return Err(LendingError::VlTransferNotAllowed);
+            }
+            if balance_from_before == amount {
+                self.set_user_collateral(asset, reserve.id, from, false);
+            }
+            if balance_to_before == 0 {
+                self.set_user_collateral(asset, reserve.id, to, true);
+            }
+            Ok(())
+        }
+    }
+
+    /// Unwraps the result of a step run after a cross-contract call already moved funds or
+    /// tokens. Returning an error would not roll that call back, so a failure traps and
+    /// reverts the whole message instead.
+    fn revert_on_error<T, E: core::fmt::Debug>(
+        action: &str,
+        result: core::result::Result<T, E>,
+    ) -> T {
+        match result {
+            Ok(value) => value,
+            Err(error) => panic!("{} failed: {:?}", action, error),
         }
     }
 }
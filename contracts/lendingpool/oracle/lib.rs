@@ -0,0 +1,127 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::oracle::PriceOracle;
+use ink_lang as ink;
+
+#[ink::contract]
+mod oracle {
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::collections::HashMap as StorageHashMap;
+
+    /// The price oracle error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller is not the oracle admin.
+        OnlyAdminAccess,
+        /// Returned if the submitted price is 0.
+        InvalidPrice,
+    }
+
+    /// The price oracle result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Event emitted when the admin feeds a new price.
+    #[ink(event)]
+    pub struct AssetPriceUpdated {
+        #[ink(topic)]
+        asset: AccountId,
+        price: u128,
+        timestamp: u64,
+    }
+
+    /// Event emitted when the feeding rights move to another account.
+    #[ink(event)]
+    pub struct AdminTransferred {
+        #[ink(topic)]
+        previous_admin: AccountId,
+        #[ink(topic)]
+        new_admin: AccountId,
+    }
+
+    /// Prices fed by a single admin account, e.g. an off-chain price relayer.
+    #[ink(storage)]
+    pub struct PriceOracle {
+        /// The account allowed to feed prices.
+        admin: AccountId,
+        /// Mapping from asset to the price of one whole token in the base currency (in wad)
+        /// and the timestamp it was fed at.
+        prices: StorageHashMap<AccountId, (u128, u64)>,
+    }
+
+    impl PriceOracle {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                admin: Self::env().caller(),
+                prices: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the price of one whole `asset` token in the base currency, in wad,
+        /// along with the timestamp of its last update.
+        ///
+        /// Returns `None` if no price was ever fed for `asset`.
+        #[ink(message)]
+        pub fn get_asset_price(&self, asset: AccountId) -> Option<(u128, u64)> {
+            self.prices.get(&asset).copied()
+        }
+
+        /// Feeds the price of one whole `asset` token in the base currency, in wad.
+        ///
+        /// On success an `AssetPriceUpdated` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `OnlyAdminAccess` error if the caller is not the admin.
+        ///
+        /// Returns `InvalidPrice` error if `price` is 0.
+        #[ink(message)]
+        pub fn set_asset_price(&mut self, asset: AccountId, price: u128) -> Result<()> {
+            self.only_admin()?;
+            if price == 0 {
+                return Err(Error::InvalidPrice);
+            }
+            let timestamp = self.env().block_timestamp();
+            self.prices.insert(asset, (price, timestamp));
+            self.env().emit_event(AssetPriceUpdated {
+                asset,
+                price,
+                timestamp,
+            });
+            Ok(())
+        }
+
+        /// Returns the account allowed to feed prices.
+        #[ink(message)]
+        pub fn admin(&self) -> AccountId {
+            self.admin
+        }
+
+        /// Hands the feeding rights to `new_admin`.
+        ///
+        /// On success an `AdminTransferred` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `OnlyAdminAccess` error if the caller is not the admin.
+        #[ink(message)]
+        pub fn transfer_admin(&mut self, new_admin: AccountId) -> Result<()> {
+            self.only_admin()?;
+            let previous_admin = self.admin;
+            self.admin = new_admin;
+            self.env().emit_event(AdminTransferred {
+                previous_admin,
+                new_admin,
+            });
+            Ok(())
+        }
+
+        fn only_admin(&self) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::OnlyAdminAccess);
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,249 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Fixed point math shared by the lending pool and the tokens of its reserves.
+//!
+//! Each contract maps `MathError` to its own error type with a `From` implementation.
+
+use primitive_types::U256;
+
+/// Errors of the fixed point operations.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MathError {
+    DivisionByZero,
+    MultiplicationOverflow,
+    AdditionOverflow,
+}
+
+type Result<T> = core::result::Result<T, MathError>;
+
+pub const WAD: u128 = 1_000_000_000_000_000_000;
+
+/// 27 decimals fixed point unit, used by indexes and rates.
+pub const RAY: u128 = 1_000_000_000_000_000_000_000_000_000;
+pub const HALF_RAY: u128 = RAY / 2;
+
+/// 100.00%, percentages are expressed in basis points.
+pub const PERCENTAGE_FACTOR: u128 = 10_000;
+pub const HALF_PERCENT: u128 = PERCENTAGE_FACTOR / 2;
+
+/// Block timestamps are in milliseconds.
+pub const ONE_YEAR: u64 = 365 * 24 * 3600 * 1000;
+pub const SECONDS_PER_YEAR: u128 = 365 * 24 * 3600;
+
+/// Computes `(a * b + rounding) / c` with a 256 bits intermediate product.
+pub fn mul_div(a: u128, b: u128, c: u128, rounding: u128) -> Result<u128> {
+    if c == 0 {
+        return Err(MathError::DivisionByZero);
+    }
+    let result = (U256::from(a) * U256::from(b) + U256::from(rounding)) / U256::from(c);
+    if result > U256::from(u128::MAX) {
+        return Err(MathError::MultiplicationOverflow);
+    }
+    Ok(result.as_u128())
+}
+
+/// Divides two wads, rounding half up.
+pub fn wad_div(a: u128, b: u128) -> Result<u128> {
+    mul_div(a, WAD, b, b / 2)
+}
+
+/// Converts a wad to a ray.
+pub fn wad_to_ray(a: u128) -> Result<u128> {
+    a.checked_mul(RAY / WAD)
+        .ok_or(MathError::MultiplicationOverflow)
+}
+
+/// Multiplies two rays, rounding half up.
+pub fn ray_mul(a: u128, b: u128) -> Result<u128> {
+    mul_div(a, b, RAY, HALF_RAY)
+}
+
+/// Divides two rays, rounding half up.
+pub fn ray_div(a: u128, b: u128) -> Result<u128> {
+    mul_div(a, RAY, b, b / 2)
+}
+
+/// Applies a percentage in basis points to `value`, rounding half up.
+pub fn percent_mul(value: u128, percentage: u128) -> Result<u128> {
+    mul_div(value, percentage, PERCENTAGE_FACTOR, HALF_PERCENT)
+}
+
+/// Divides `value` by a percentage in basis points, rounding half up.
+pub fn percent_div(value: u128, percentage: u128) -> Result<u128> {
+    mul_div(value, PERCENTAGE_FACTOR, percentage, percentage / 2)
+}
+
+/// Values `amount` of a token with `decimals` in the base currency, given the `price` of one token.
+pub fn to_base_currency(amount: u128, price: u128, decimals: u8) -> Result<u128> {
+    mul_div(amount, price, 10u128.pow(decimals as u32), 0)
+}
+
+/// Converts a base currency `value` back to an amount of a token with `decimals`.
+pub fn from_base_currency(value: u128, price: u128, decimals: u8) -> Result<u128> {
+    mul_div(value, 10u128.pow(decimals as u32), price, 0)
+}
+
+/// Ray factor of the interest accrued linearly by a yearly `rate` since `last_update_timestamp`.
+pub fn calculate_linear_interest(rate: u128, last_update_timestamp: u64, now: u64) -> Result<u128> {
+    let elapsed = now.saturating_sub(last_update_timestamp) as u128;
+    let interest = mul_div(rate, elapsed, ONE_YEAR as u128, 0)?;
+    RAY.checked_add(interest).ok_or(MathError::AdditionOverflow)
+}
+
+/// Ray factor of the interest compounded each second by a yearly `rate` since `last_update_timestamp`.
+///
+/// Uses the first three terms of the binomial expansion of `(1 + rate / SECONDS_PER_YEAR) ^ t`,
+/// which slightly underestimates the interest at high rates in exchange of a bounded cost.
+pub fn calculate_compounded_interest(
+    rate: u128,
+    last_update_timestamp: u64,
+    now: u64,
+) -> Result<u128> {
+    let exp = (now.saturating_sub(last_update_timestamp) / 1000) as u128;
+    if exp == 0 {
+        return Ok(RAY);
+    }
+    let exp_minus_one = exp - 1;
+    let exp_minus_two = exp.saturating_sub(2);
+
+    let rate_per_second = rate / SECONDS_PER_YEAR;
+    let base_power_two = ray_mul(rate_per_second, rate_per_second)?;
+    let base_power_three = ray_mul(base_power_two, rate_per_second)?;
+
+    let first_term = U256::from(rate_per_second) * U256::from(exp);
+    let second_term =
+        U256::from(exp) * U256::from(exp_minus_one) * U256::from(base_power_two) / U256::from(2);
+    let third_term = U256::from(exp)
+        * U256::from(exp_minus_one)
+        * U256::from(exp_minus_two)
+        * U256::from(base_power_three)
+        / U256::from(6);
+
+    let result = U256::from(RAY) + first_term + second_term + third_term;
+    if result > U256::from(u128::MAX) {
+        return Err(MathError::AdditionOverflow);
+    }
+    Ok(result.as_u128())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mul_div_rounds_with_the_given_rounding() {
+        assert_eq!(mul_div(7, 1, 2, 0), Ok(3));
+        assert_eq!(mul_div(7, 1, 2, 1), Ok(4));
+        // the product doesn't fit in 128 bits, the result does
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX, 0), Ok(u128::MAX));
+    }
+
+    #[test]
+    fn mul_div_rejects_zero_divisor_and_overflow() {
+        assert_eq!(mul_div(1, 1, 0, 0), Err(MathError::DivisionByZero));
+        assert_eq!(
+            mul_div(u128::MAX, 2, 1, 0),
+            Err(MathError::MultiplicationOverflow)
+        );
+        assert_eq!(
+            mul_div(u128::MAX, u128::MAX, u128::MAX, u128::MAX),
+            Err(MathError::MultiplicationOverflow)
+        );
+    }
+
+    #[test]
+    fn ray_operations_round_half_up() {
+        assert_eq!(ray_mul(1, HALF_RAY), Ok(1));
+        assert_eq!(ray_mul(1, HALF_RAY - 1), Ok(0));
+        assert_eq!(ray_div(1, 2 * RAY), Ok(1));
+        assert_eq!(ray_div(1, 2 * RAY + 2), Ok(0));
+        assert_eq!(ray_mul(3 * RAY, 2 * RAY), Ok(6 * RAY));
+        assert_eq!(
+            ray_div(RAY, 3 * RAY),
+            Ok(333_333_333_333_333_333_333_333_333)
+        );
+        assert_eq!(
+            ray_div(2 * RAY, 3 * RAY),
+            Ok(666_666_666_666_666_666_666_666_667)
+        );
+    }
+
+    #[test]
+    fn percent_and_wad_operations_round_half_up() {
+        assert_eq!(percent_mul(1, HALF_PERCENT), Ok(1));
+        assert_eq!(percent_mul(1, HALF_PERCENT - 1), Ok(0));
+        assert_eq!(percent_mul(1_000, 7_500), Ok(750));
+        assert_eq!(percent_div(750, 7_500), Ok(1_000));
+        assert_eq!(wad_div(2 * WAD, 3 * WAD), Ok(666_666_666_666_666_667));
+    }
+
+    #[test]
+    fn zero_inputs() {
+        assert_eq!(ray_mul(0, u128::MAX), Ok(0));
+        assert_eq!(ray_div(0, RAY), Ok(0));
+        assert_eq!(ray_div(RAY, 0), Err(MathError::DivisionByZero));
+        assert_eq!(wad_div(WAD, 0), Err(MathError::DivisionByZero));
+        assert_eq!(percent_div(1, 0), Err(MathError::DivisionByZero));
+        assert_eq!(from_base_currency(1, 0, 10), Err(MathError::DivisionByZero));
+        assert_eq!(wad_to_ray(0), Ok(0));
+    }
+
+    #[test]
+    fn overflowing_inputs() {
+        assert_eq!(
+            ray_mul(u128::MAX, 2 * RAY),
+            Err(MathError::MultiplicationOverflow)
+        );
+        assert_eq!(
+            ray_div(u128::MAX, 1),
+            Err(MathError::MultiplicationOverflow)
+        );
+        assert_eq!(
+            percent_div(u128::MAX, 1),
+            Err(MathError::MultiplicationOverflow)
+        );
+        assert_eq!(
+            wad_to_ray(u128::MAX / 1_000_000_000 + 1),
+            Err(MathError::MultiplicationOverflow)
+        );
+    }
+
+    #[test]
+    fn wad_to_ray_scales_by_nine_decimals() {
+        assert_eq!(wad_to_ray(WAD), Ok(RAY));
+        assert_eq!(wad_to_ray(1), Ok(1_000_000_000));
+    }
+
+    #[test]
+    fn base_currency_round_trip() {
+        // 1.5 tokens of 10 decimals at a price of 2 in wad
+        let value = to_base_currency(15_000_000_000, 2 * WAD, 10).unwrap();
+        assert_eq!(value, 3 * WAD);
+        assert_eq!(from_base_currency(value, 2 * WAD, 10), Ok(15_000_000_000));
+    }
+
+    #[test]
+    fn interest_over_one_year_at_ten_percent() {
+        let rate = RAY / 10;
+        let start = 1_000;
+        let end = start + ONE_YEAR;
+
+        assert_eq!(
+            calculate_linear_interest(rate, start, end),
+            Ok(1_100_000_000_000_000_000_000_000_000)
+        );
+        // e^0.1 = 1.10517091807..., the three terms expansion stays just below it
+        assert_eq!(
+            calculate_compounded_interest(rate, start, end),
+            Ok(1_105_167_270_015_202_188_556_648_000)
+        );
+    }
+
+    #[test]
+    fn no_interest_without_elapsed_time() {
+        assert_eq!(calculate_linear_interest(RAY, 5_000, 5_000), Ok(RAY));
+        assert_eq!(calculate_compounded_interest(RAY, 5_000, 5_000), Ok(RAY));
+        // compounding happens each second, less than one second accrues nothing
+        assert_eq!(calculate_compounded_interest(RAY, 5_000, 5_999), Ok(RAY));
+    }
+}
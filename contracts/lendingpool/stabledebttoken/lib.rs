@@ -0,0 +1,482 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::stabledebttoken::StableDebtToken;
+use ink_lang as ink;
+
+/// Debt of the stable rate borrowers of a reserve.
+///
+/// Each user keeps the rate it borrowed at, averaged with the rate of its later borrows,
+/// and its balance accrues interest at that rate until the pool burns or rebalances it.
+/// The token can't be transferred, only the lending pool mints and burns it. Instead, a user
+/// can delegate credit to another account, which can then borrow on their behalf up to the
+/// delegated amount.
+#[ink::contract]
+mod stabledebttoken {
+    use ink_prelude::string::String;
+    use interfaces::LendingError;
+    use raymath::{calculate_compounded_interest, ray_div, ray_mul, MathError};
+
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::collections::HashMap as StorageHashMap;
+
+    /// The stable debt token error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller is not the lending pool.
+        CallerMustBeLendingPool,
+        /// Returned if the amount to burn is above the debt of the user.
+        BurnExceedsBalance,
+        /// Returned if a debt or a rate overflows.
+        StableDebtOverflow,
+        /// Returned if the borrower was not delegated enough credit by the debt holder.
+        BorrowAllowanceNotEnough,
+        /// Returned on transfers and approvals, debt can't be moved.
+        OperationNotSupported,
+    }
+
+    /// The stable debt token result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl From<MathError> for Error {
+        fn from(_: MathError) -> Self {
+            Error::StableDebtOverflow
+        }
+    }
+
+    impl From<Error> for LendingError {
+        fn from(error: Error) -> Self {
+            match error {
+                Error::CallerMustBeLendingPool => LendingError::CtCallerMustBeLendingPool,
+                Error::BurnExceedsBalance => LendingError::SdtBurnExceedsBalance,
+                Error::StableDebtOverflow => LendingError::SdtStableDebtOverflow,
+                Error::BorrowAllowanceNotEnough => LendingError::CtBorrowAllowanceNotEnough,
+                Error::OperationNotSupported => LendingError::VlTransferNotAllowed,
+            }
+        }
+    }
+
+    /// Event emitted when new stable debt is minted.
+    #[ink(event)]
+    pub struct Mint {
+        /// The account receiving the borrowed funds.
+        #[ink(topic)]
+        user: AccountId,
+        /// The account taking the debt.
+        #[ink(topic)]
+        on_behalf_of: AccountId,
+        amount: Balance,
+        /// Interest accrued by the user since their last action.
+        balance_increase: Balance,
+        /// Stable rate of the user after the mint.
+        new_rate: u128,
+        avg_stable_rate: u128,
+        new_total_supply: Balance,
+    }
+
+    /// Event emitted when stable debt is burnt.
+    #[ink(event)]
+    pub struct Burn {
+        #[ink(topic)]
+        user: AccountId,
+        amount: Balance,
+        /// Interest accrued by the user since their last action.
+        balance_increase: Balance,
+        avg_stable_rate: u128,
+        new_total_supply: Balance,
+    }
+
+    /// Event emitted when `from_user` lets `to_user` borrow up to `amount` on their behalf.
+    #[ink(event)]
+    pub struct BorrowAllowanceDelegated {
+        #[ink(topic)]
+        from_user: AccountId,
+        #[ink(topic)]
+        to_user: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(storage)]
+    pub struct StableDebtToken {
+        /// The lending pool, the only account allowed to mint and burn.
+        pool: AccountId,
+        /// The asset borrowed.
+        underlying_asset: AccountId,
+        name: String,
+        symbol: String,
+        decimals: u8,
+        /// Mapping from user to their debt as of their last action.
+        principal_balances: StorageHashMap<AccountId, Balance>,
+        /// Mapping from user to their stable rate, in ray, and the timestamp of their last action.
+        user_rates: StorageHashMap<AccountId, (u128, u64)>,
+        /// Debt of all the users as of `total_supply_timestamp`.
+        total_supply: Balance,
+        /// Stable rate of the users weighted by their debt, in ray.
+        avg_stable_rate: u128,
+        total_supply_timestamp: u64,
+        /// Mapping from (delegator, delegatee) to the amount the delegatee can still borrow
+        /// on behalf of the delegator.
+        borrow_allowances: StorageHashMap<(AccountId, AccountId), Balance>,
+    }
+
+    impl StableDebtToken {
+        #[ink(constructor)]
+        pub fn new(
+            pool: AccountId,
+            underlying_asset: AccountId,
+            name: String,
+            symbol: String,
+            decimals: u8,
+        ) -> Self {
+            Self {
+                pool,
+                underlying_asset,
+                name,
+                symbol,
+                decimals,
+                principal_balances: StorageHashMap::new(),
+                user_rates: StorageHashMap::new(),
+                total_supply: 0,
+                avg_stable_rate: 0,
+                total_supply_timestamp: 0,
+                borrow_allowances: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the token name.
+        #[ink(message)]
+        pub fn token_name(&self) -> String {
+            self.name.clone()
+        }
+
+        /// Returns the token symbol.
+        #[ink(message)]
+        pub fn token_symbol(&self) -> String {
+            self.symbol.clone()
+        }
+
+        /// Returns the token decimals, the ones of the underlying asset.
+        #[ink(message)]
+        pub fn token_decimals(&self) -> u8 {
+            self.decimals
+        }
+
+        /// Returns the asset borrowed.
+        #[ink(message)]
+        pub fn underlying_asset(&self) -> AccountId {
+            self.underlying_asset
+        }
+
+        /// Returns the lending pool.
+        #[ink(message)]
+        pub fn pool(&self) -> AccountId {
+            self.pool
+        }
+
+        /// Returns the debt of `user`, including the interest accrued at their stable rate.
+        #[ink(message)]
+        pub fn balance_of(&self, user: AccountId) -> Balance {
+            self.accrued_balance_of(user).unwrap_or(Balance::MAX)
+        }
+
+        /// Returns the debt of `user` as of their last action.
+        #[ink(message)]
+        pub fn principal_balance_of(&self, user: AccountId) -> Balance {
+            self.principal_balances.get(&user).copied().unwrap_or(0)
+        }
+
+        /// Returns the stable rate of `user`, in ray.
+        #[ink(message)]
+        pub fn get_user_stable_rate(&self, user: AccountId) -> u128 {
+            self.user_rates.get(&user).map(|(rate, _)| *rate).unwrap_or(0)
+        }
+
+        /// Returns the timestamp of the last action of `user`.
+        #[ink(message)]
+        pub fn get_user_last_updated(&self, user: AccountId) -> u64 {
+            self.user_rates
+                .get(&user)
+                .map(|(_, timestamp)| *timestamp)
+                .unwrap_or(0)
+        }
+
+        /// Returns the debt of all the users, including the interest accrued at the average
+        /// stable rate.
+        #[ink(message)]
+        pub fn total_supply(&self) -> Balance {
+            self.accrued_total_supply().unwrap_or(Balance::MAX)
+        }
+
+        /// Returns the stable rate of the users weighted by their debt, in ray.
+        #[ink(message)]
+        pub fn get_average_stable_rate(&self) -> u128 {
+            self.avg_stable_rate
+        }
+
+        /// Returns the timestamp the total supply was last updated at.
+        #[ink(message)]
+        pub fn get_total_supply_last_updated(&self) -> u64 {
+            self.total_supply_timestamp
+        }
+
+        /// Returns the accrued total supply along with the average stable rate.
+        #[ink(message)]
+        pub fn get_total_supply_and_avg_rate(&self) -> (Balance, u128) {
+            (self.total_supply(), self.avg_stable_rate)
+        }
+
+        /// Returns the total supply as of its last update, the accrued total supply, the
+        /// average stable rate and the timestamp of the last update.
+        #[ink(message)]
+        pub fn get_supply_data(&self) -> (Balance, Balance, u128, u64) {
+            (
+                self.total_supply,
+                self.total_supply(),
+                self.avg_stable_rate,
+                self.total_supply_timestamp,
+            )
+        }
+
+        /// Lets `delegatee` borrow up to `amount` on behalf of the caller, who takes the debt.
+        ///
+        /// If this function is called again it overwrites the current allowance with `amount`.
+        ///
+        /// A `BorrowAllowanceDelegated` event is emitted.
+        #[ink(message)]
+        pub fn approve_delegation(&mut self, delegatee: AccountId, amount: Balance) -> Result<()> {
+            let delegator = self.env().caller();
+            self.borrow_allowances.insert((delegator, delegatee), amount);
+            self.env().emit_event(BorrowAllowanceDelegated {
+                from_user: delegator,
+                to_user: delegatee,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Returns the amount `to_user` can still borrow on behalf of `from_user`.
+        #[ink(message)]
+        pub fn borrow_allowance(&self, from_user: AccountId, to_user: AccountId) -> Balance {
+            self.borrow_allowances
+                .get(&(from_user, to_user))
+                .copied()
+                .unwrap_or(0)
+        }
+
+        /// Always fails, debt can't be transferred.
+        #[ink(message)]
+        pub fn transfer(&mut self, _to: AccountId, _value: Balance) -> Result<()> {
+            Err(Error::OperationNotSupported)
+        }
+
+        /// Always fails, debt can't be transferred.
+        #[ink(message)]
+        pub fn transfer_from(
+            &mut self,
+            _from: AccountId,
+            _to: AccountId,
+            _value: Balance,
+        ) -> Result<()> {
+            Err(Error::OperationNotSupported)
+        }
+
+        /// Always fails, use `approve_delegation` to let another account borrow on your behalf.
+        #[ink(message)]
+        pub fn approve(&mut self, _spender: AccountId, _value: Balance) -> Result<()> {
+            Err(Error::OperationNotSupported)
+        }
+
+        /// Always returns `0`, debt can't be transferred.
+        #[ink(message)]
+        pub fn allowance(&self, _owner: AccountId, _spender: AccountId) -> Balance {
+            0
+        }
+
+        /// Adds `amount` of debt borrowed at `rate` to `on_behalf_of`, for funds borrowed by
+        /// `user`, averaging their stable rate with `rate` and accruing their interest into
+        /// their balance.
+        ///
+        /// When `user` borrows on behalf of someone else, their borrow allowance decreases
+        /// by `amount`.
+        ///
+        /// On success a `Mint` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `CallerMustBeLendingPool` error if the caller is not the pool.
+        ///
+        /// Returns `BorrowAllowanceNotEnough` error if `user` was not delegated enough credit.
+        ///
+        /// Returns `StableDebtOverflow` error if the new debt or rates overflow.
+        #[ink(message)]
+        pub fn mint(
+            &mut self,
+            user: AccountId,
+            on_behalf_of: AccountId,
+            amount: Balance,
+            rate: u128,
+        ) -> Result<()> {
+            self.only_pool()?;
+            if user != on_behalf_of {
+                self.decrease_borrow_allowance(on_behalf_of, user, amount)?;
+            }
+            let now = self.env().block_timestamp();
+            let (principal, current_balance) = self.balances_of(on_behalf_of)?;
+            let balance_increase = current_balance - principal;
+            let next_balance = current_balance
+                .checked_add(amount)
+                .ok_or(Error::StableDebtOverflow)?;
+            let new_rate = ray_div(
+                ray_mul(self.get_user_stable_rate(on_behalf_of), current_balance)?
+                    .checked_add(ray_mul(rate, amount)?)
+                    .ok_or(Error::StableDebtOverflow)?,
+                next_balance,
+            )?;
+
+            let previous_supply = self.accrued_total_supply()?;
+            let next_supply = previous_supply
+                .checked_add(amount)
+                .ok_or(Error::StableDebtOverflow)?;
+            self.avg_stable_rate = ray_div(
+                ray_mul(self.avg_stable_rate, previous_supply)?
+                    .checked_add(ray_mul(rate, amount)?)
+                    .ok_or(Error::StableDebtOverflow)?,
+                next_supply,
+            )?;
+            self.total_supply = next_supply;
+            self.total_supply_timestamp = now;
+
+            self.principal_balances.insert(on_behalf_of, next_balance);
+            self.user_rates.insert(on_behalf_of, (new_rate, now));
+
+            self.env().emit_event(Mint {
+                user,
+                on_behalf_of,
+                amount,
+                balance_increase,
+                new_rate,
+                avg_stable_rate: self.avg_stable_rate,
+                new_total_supply: next_supply,
+            });
+            Ok(())
+        }
+
+        /// Removes `amount` of debt from `user`, accruing their interest into their balance first.
+        ///
+        /// On success a `Burn` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `CallerMustBeLendingPool` error if the caller is not the pool.
+        ///
+        /// Returns `BurnExceedsBalance` error if `amount` is above the debt of the user.
+        #[ink(message)]
+        pub fn burn(&mut self, user: AccountId, amount: Balance) -> Result<()> {
+            self.only_pool()?;
+            let now = self.env().block_timestamp();
+            let (principal, current_balance) = self.balances_of(user)?;
+            if amount > current_balance {
+                return Err(Error::BurnExceedsBalance);
+            }
+            let balance_increase = current_balance - principal;
+            let user_rate = self.get_user_stable_rate(user);
+
+            // the accrued total can be off by a few units from the sum of the user debts
+            let previous_supply = self.accrued_total_supply()?;
+            if previous_supply <= amount {
+                self.avg_stable_rate = 0;
+                self.total_supply = 0;
+            } else {
+                let next_supply = previous_supply - amount;
+                let first_term = ray_mul(self.avg_stable_rate, previous_supply)?;
+                let second_term = ray_mul(user_rate, amount)?;
+                if second_term >= first_term {
+                    self.avg_stable_rate = 0;
+                    self.total_supply = 0;
+                } else {
+                    self.avg_stable_rate = ray_div(first_term - second_term, next_supply)?;
+                    self.total_supply = next_supply;
+                }
+            }
+            self.total_supply_timestamp = now;
+
+            if amount == current_balance {
+                self.principal_balances.take(&user);
+                self.user_rates.take(&user);
+            } else {
+                self.principal_balances.insert(user, current_balance - amount);
+                self.user_rates.insert(user, (user_rate, now));
+            }
+
+            self.env().emit_event(Burn {
+                user,
+                amount,
+                balance_increase,
+                avg_stable_rate: self.avg_stable_rate,
+                new_total_supply: self.total_supply,
+            });
+            Ok(())
+        }
+
+        fn only_pool(&self) -> Result<()> {
+            if self.env().caller() != self.pool {
+                return Err(Error::CallerMustBeLendingPool);
+            }
+            Ok(())
+        }
+
+        fn decrease_borrow_allowance(
+            &mut self,
+            delegator: AccountId,
+            delegatee: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            let allowance = self.borrow_allowance(delegator, delegatee);
+            if amount > allowance {
+                return Err(Error::BorrowAllowanceNotEnough);
+            }
+            let new_allowance = allowance - amount;
+            self.borrow_allowances
+                .insert((delegator, delegatee), new_allowance);
+            self.env().emit_event(BorrowAllowanceDelegated {
+                from_user: delegator,
+                to_user: delegatee,
+                amount: new_allowance,
+            });
+            Ok(())
+        }
+
+        /// Returns the `(principal, accrued)` debt of `user`.
+        fn balances_of(&self, user: AccountId) -> Result<(Balance, Balance)> {
+            let principal = self.principal_balance_of(user);
+            Ok((principal, self.accrued_balance_of(user)?))
+        }
+
+        fn accrued_balance_of(&self, user: AccountId) -> Result<Balance> {
+            let principal = self.principal_balance_of(user);
+            if principal == 0 {
+                return Ok(0);
+            }
+            let (rate, last_update_timestamp) =
+                self.user_rates.get(&user).copied().unwrap_or_default();
+            let cumulated = calculate_compounded_interest(
+                rate,
+                last_update_timestamp,
+                self.env().block_timestamp(),
+            )?;
+            Ok(ray_mul(principal, cumulated)?)
+        }
+
+        fn accrued_total_supply(&self) -> Result<Balance> {
+            if self.total_supply == 0 {
+                return Ok(0);
+            }
+            let cumulated = calculate_compounded_interest(
+                self.avg_stable_rate,
+                self.total_supply_timestamp,
+                self.env().block_timestamp(),
+            )?;
+            Ok(ray_mul(self.total_supply, cumulated)?)
+        }
+    }
+}
@@ -0,0 +1,474 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::stoken::Stoken;
+use ink_lang as ink;
+
+/// Interest bearing token of the depositors of a reserve.
+///
+/// Balances are stored divided by the liquidity index of the reserve at the time of each
+/// action, so multiplying them by the current index makes them grow with the interest
+/// earned by the reserve. Only the lending pool mints and burns it, and it validates every
+/// transfer against the health factor of the sender.
+#[ink::contract]
+mod stoken {
+    use ink_env::call::FromAccountId;
+    use ink_prelude::string::String;
+    use interfaces::{Callee, LendingError, PoolCallbacks};
+    use raymath::{ray_div, ray_mul, MathError};
+
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::collections::HashMap as StorageHashMap;
+
+    /// The sToken error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller is not the lending pool.
+        CallerMustBeLendingPool,
+        /// Returned if not enough balance to fulfill a request is available.
+        InsufficientBalance,
+        /// Returned if not enough allowance to fulfill a request is available.
+        InsufficientAllowance,
+        /// Returned if the amount to mint is 0 once scaled.
+        InvalidMintAmount,
+        /// Returned if the amount to burn is 0 once scaled.
+        InvalidBurnAmount,
+        /// Returned if a balance overflows.
+        Overflow,
+        /// Returned if the pool rejects the transfer, e.g. as it would leave the sender
+        /// without enough collateral for their debt.
+        TransferNotAllowed,
+    }
+
+    /// The sToken result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl From<MathError> for Error {
+        fn from(_: MathError) -> Self {
+            Error::Overflow
+        }
+    }
+
+    impl From<Error> for LendingError {
+        fn from(error: Error) -> Self {
+            match error {
+                Error::CallerMustBeLendingPool => LendingError::CtCallerMustBeLendingPool,
+                Error::InsufficientBalance => LendingError::Erc20InsufficientBalance,
+                Error::InsufficientAllowance => LendingError::Erc20InsufficientAllowance,
+                Error::InvalidMintAmount => LendingError::CtInvalidMintAmount,
+                Error::InvalidBurnAmount => LendingError::CtInvalidBurnAmount,
+                Error::Overflow => LendingError::MathMultiplicationOverflow,
+                Error::TransferNotAllowed => LendingError::VlTransferNotAllowed,
+            }
+        }
+    }
+
+    /// Event emitted when a token transfer occurs, in underlying units.
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        value: Balance,
+    }
+
+    /// Event emitted when an approval occurs that `spender` is allowed to withdraw
+    /// up to the amount of `value` tokens from `owner`.
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        value: Balance,
+    }
+
+    /// Event emitted when the pool mints sTokens for a deposit.
+    #[ink(event)]
+    pub struct Mint {
+        #[ink(topic)]
+        user: AccountId,
+        amount: Balance,
+        /// Liquidity index the amount was scaled by.
+        index: u128,
+    }
+
+    /// Event emitted when the pool burns sTokens for a withdrawal or a liquidation.
+    #[ink(event)]
+    pub struct Burn {
+        #[ink(topic)]
+        user: AccountId,
+        amount: Balance,
+        /// Liquidity index the amount was scaled by.
+        index: u128,
+    }
+
+    #[ink(storage)]
+    pub struct Stoken {
+        /// The lending pool, the only account allowed to mint and burn.
+        pool: AccountId,
+        /// The asset deposited.
+        underlying_asset: AccountId,
+        name: String,
+        symbol: String,
+        decimals: u8,
+        /// Mapping from owner to their balance divided by the liquidity index.
+        scaled_balances: StorageHashMap<AccountId, Balance>,
+        /// Sum of the scaled balances.
+        scaled_total_supply: Balance,
+        /// Mapping of the token amount which an account is allowed to withdraw
+        /// from another account, in underlying units.
+        allowances: StorageHashMap<(AccountId, AccountId), Balance>,
+    }
+
+    impl Stoken {
+        #[ink(constructor)]
+        pub fn new(
+            pool: AccountId,
+            underlying_asset: AccountId,
+            name: String,
+            symbol: String,
+            decimals: u8,
+        ) -> Self {
+            Self {
+                pool,
+                underlying_asset,
+                name,
+                symbol,
+                decimals,
+                scaled_balances: StorageHashMap::new(),
+                scaled_total_supply: 0,
+                allowances: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the token name.
+        #[ink(message)]
+        pub fn token_name(&self) -> String {
+            self.name.clone()
+        }
+
+        /// Returns the token symbol.
+        #[ink(message)]
+        pub fn token_symbol(&self) -> String {
+            self.symbol.clone()
+        }
+
+        /// Returns the token decimals, the ones of the underlying asset.
+        #[ink(message)]
+        pub fn token_decimals(&self) -> u8 {
+            self.decimals
+        }
+
+        /// Returns the asset deposited.
+        #[ink(message)]
+        pub fn underlying_asset(&self) -> AccountId {
+            self.underlying_asset
+        }
+
+        /// Returns the lending pool.
+        #[ink(message)]
+        pub fn pool(&self) -> AccountId {
+            self.pool
+        }
+
+        /// Returns the deposit of `owner`, including the interest earned since.
+        ///
+        /// Queries the liquidity index from the pool, so the pool itself must use
+        /// `scaled_balance_of` instead.
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId) -> Balance {
+            ray_mul(self.scaled_balance_of(owner), self.normalized_income()).unwrap_or(Balance::MAX)
+        }
+
+        /// Returns the deposits of all the users, including the interest earned since.
+        #[ink(message)]
+        pub fn total_supply(&self) -> Balance {
+            ray_mul(self.scaled_total_supply, self.normalized_income()).unwrap_or(Balance::MAX)
+        }
+
+        /// Returns the balance of `user` divided by the liquidity index, as stored on the token.
+        #[ink(message)]
+        pub fn scaled_balance_of(&self, user: AccountId) -> Balance {
+            self.scaled_balances.get(&user).copied().unwrap_or(0)
+        }
+
+        /// Returns the total supply divided by the liquidity index, as stored on the token.
+        #[ink(message)]
+        pub fn scaled_total_supply(&self) -> Balance {
+            self.scaled_total_supply
+        }
+
+        /// Returns the scaled balance of `user` along with the scaled total supply.
+        #[ink(message)]
+        pub fn get_scaled_user_balance_and_supply(&self, user: AccountId) -> (Balance, Balance) {
+            (self.scaled_balance_of(user), self.scaled_total_supply)
+        }
+
+        /// Transfers `value` amount of tokens from the caller's account to account `to`.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the caller's account balance.
+        ///
+        /// Returns `TransferNotAllowed` error if the pool rejects the transfer.
+        #[ink(message)]
+        pub fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            let from = self.env().caller();
+            self.transfer_from_to(from, to, value)
+        }
+
+        /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+        ///
+        /// Returns `0` if no allowance has been set.
+        #[ink(message)]
+        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get(&(owner, spender)).copied().unwrap_or(0)
+        }
+
+        /// Transfers `value` tokens on the behalf of `from` to the account `to`.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientAllowance` error if there are not enough tokens allowed
+        /// for the caller to withdraw from `from`.
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the the account balance of `from`.
+        ///
+        /// Returns `TransferNotAllowed` error if the pool rejects the transfer.
+        #[ink(message)]
+        pub fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            let allowance = self.allowance(from, caller);
+            if allowance < value {
+                return Err(Error::InsufficientAllowance);
+            }
+            self.transfer_from_to(from, to, value)?;
+            self.allowances.insert((from, caller), allowance - value);
+            Ok(())
+        }
+
+        /// Allows `spender` to withdraw from the caller's account multiple times, up to
+        /// the `value` amount.
+        ///
+        /// If this function is called again it overwrites the current allowance with `value`.
+        ///
+        /// An `Approval` event is emitted.
+        #[ink(message)]
+        pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
+            let owner = self.env().caller();
+            self.allowances.insert((owner, spender), value);
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value,
+            });
+            Ok(())
+        }
+
+        /// Mints `amount` of sTokens to `user`, scaled by the liquidity `index` of the reserve.
+        ///
+        /// On success `Transfer` and `Mint` events are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `CallerMustBeLendingPool` error if the caller is not the pool.
+        ///
+        /// Returns `InvalidMintAmount` error if `amount` is 0 once scaled.
+        #[ink(message)]
+        pub fn mint(&mut self, user: AccountId, amount: Balance, index: u128) -> Result<()> {
+            self.only_pool()?;
+            let amount_scaled = ray_div(amount, index)?;
+            if amount_scaled == 0 {
+                return Err(Error::InvalidMintAmount);
+            }
+            let user_balance = self
+                .scaled_balance_of(user)
+                .checked_add(amount_scaled)
+                .ok_or(Error::Overflow)?;
+            self.scaled_total_supply = self
+                .scaled_total_supply
+                .checked_add(amount_scaled)
+                .ok_or(Error::Overflow)?;
+            self.scaled_balances.insert(user, user_balance);
+
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(user),
+                value: amount,
+            });
+            self.env().emit_event(Mint {
+                user,
+                amount,
+                index,
+            });
+            Ok(())
+        }
+
+        /// Burns `amount` of sTokens from `user`, scaled by the liquidity `index` of the reserve.
+        ///
+        /// Burning the whole balance of `user` at `index` clears it, whatever the rounding.
+        ///
+        /// On success `Transfer` and `Burn` events are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `CallerMustBeLendingPool` error if the caller is not the pool.
+        ///
+        /// Returns `InsufficientBalance` error if `amount` is above the balance of `user`.
+        ///
+        /// Returns `InvalidBurnAmount` error if `amount` is 0 once scaled.
+        #[ink(message)]
+        pub fn burn(&mut self, user: AccountId, amount: Balance, index: u128) -> Result<()> {
+            self.only_pool()?;
+            let amount_scaled = self.scale_from(user, amount, index)?;
+            if amount_scaled == 0 {
+                return Err(Error::InvalidBurnAmount);
+            }
+            let user_balance = self.scaled_balance_of(user) - amount_scaled;
+            self.scaled_total_supply = self.scaled_total_supply.saturating_sub(amount_scaled);
+            if user_balance == 0 {
+                self.scaled_balances.take(&user);
+            } else {
+                self.scaled_balances.insert(user, user_balance);
+            }
+
+            self.env().emit_event(Transfer {
+                from: Some(user),
+                to: None,
+                value: amount,
+            });
+            self.env().emit_event(Burn {
+                user,
+                amount,
+                index,
+            });
+            Ok(())
+        }
+
+        /// Moves `amount` of sTokens of the liquidated `from` to the liquidator `to`, scaled by
+        /// the liquidity `index` of the reserve.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `CallerMustBeLendingPool` error if the caller is not the pool.
+        ///
+        /// Returns `InsufficientBalance` error if `amount` is above the balance of `from`.
+        #[ink(message)]
+        pub fn transfer_on_liquidation(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+            index: u128,
+        ) -> Result<()> {
+            self.only_pool()?;
+            let amount_scaled = self.scale_from(from, amount, index)?;
+            self.move_scaled(from, to, amount_scaled)?;
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                value: amount,
+            });
+            Ok(())
+        }
+
+        fn only_pool(&self) -> Result<()> {
+            if self.env().caller() != self.pool {
+                return Err(Error::CallerMustBeLendingPool);
+            }
+            Ok(())
+        }
+
+        /// Returns the liquidity index of the reserve at the current block, from the pool.
+        fn normalized_income(&self) -> u128 {
+            let pool: Callee = FromAccountId::from_account_id(self.pool);
+            pool.get_reserve_normalized_income(self.underlying_asset)
+        }
+
+        /// Scales `amount` of the balance of `owner` by `index`, the whole scaled balance if
+        /// `amount` is the whole balance.
+        fn scale_from(&self, owner: AccountId, amount: Balance, index: u128) -> Result<Balance> {
+            let scaled_balance = self.scaled_balance_of(owner);
+            let balance = ray_mul(scaled_balance, index)?;
+            if amount > balance {
+                return Err(Error::InsufficientBalance);
+            }
+            if amount == balance {
+                return Ok(scaled_balance);
+            }
+            let amount_scaled = ray_div(amount, index)?;
+            Ok(if amount_scaled > scaled_balance {
+                scaled_balance
+            } else {
+                amount_scaled
+            })
+        }
+
+        fn move_scaled(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount_scaled: Balance,
+        ) -> Result<()> {
+            let from_balance = self.scaled_balance_of(from);
+            if from_balance < amount_scaled {
+                return Err(Error::InsufficientBalance);
+            }
+            self.scaled_balances
+                .insert(from, from_balance - amount_scaled);
+            let to_balance = self
+                .scaled_balance_of(to)
+                .checked_add(amount_scaled)
+                .ok_or(Error::Overflow)?;
+            self.scaled_balances.insert(to, to_balance);
+            Ok(())
+        }
+
+        /// Transfers `value` of the balance of `from` to `to`, scaled by the current liquidity
+        /// index, once the pool has checked `from` keeps enough collateral for their debt.
+        fn transfer_from_to(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            let index = self.normalized_income();
+            let amount_scaled = self.scale_from(from, value, index)?;
+            let balance_from_before = ray_mul(self.scaled_balance_of(from), index)?;
+            let balance_to_before = ray_mul(self.scaled_balance_of(to), index)?;
+
+            let mut pool: Callee = FromAccountId::from_account_id(self.pool);
+            pool.finalize_transfer(
+                self.underlying_asset,
+                from,
+                to,
+                value,
+                balance_from_before,
+                balance_to_before,
+            )
+            .map_err(|_| Error::TransferNotAllowed)?;
+            self.move_scaled(from, to, amount_scaled)?;
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                value,
+            });
+            Ok(())
+        }
+    }
+}
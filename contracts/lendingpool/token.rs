@@ -0,0 +1,67 @@
+use crate::types::Result;
+use erc20::Erc20;
+#[cfg(not(feature = "psp22"))]
+use erc20::IErc20;
+#[cfg(feature = "psp22")]
+use erc20::PSP22;
+use ink_env::{call::FromAccountId, AccountId, DefaultEnvironment};
+#[cfg(feature = "psp22")]
+use ink_prelude::vec::Vec;
+
+/// Client of the underlying asset of a reserve.
+///
+/// Calls the `PSP22` messages of the token when built with the `psp22` feature, the
+/// legacy `IErc20` ones otherwise. Either way the token errors come back as `LendingError`.
+pub struct Token {
+    inner: Erc20,
+}
+
+impl FromAccountId<DefaultEnvironment> for Token {
+    fn from_account_id(account_id: AccountId) -> Self {
+        Self {
+            inner: FromAccountId::from_account_id(account_id),
+        }
+    }
+}
+
+#[cfg(not(feature = "psp22"))]
+impl Token {
+    /// Returns the balance of `owner`.
+    pub fn balance_of(&self, owner: AccountId) -> u128 {
+        IErc20::balance_of(&self.inner, owner)
+    }
+
+    /// Transfers `value` tokens from the pool to `to`.
+    pub fn transfer(&mut self, to: AccountId, value: u128) -> Result<()> {
+        Ok(IErc20::transfer(&mut self.inner, to, value)?)
+    }
+
+    /// Transfers `value` tokens from `from` to `to`, out of the allowance of the pool.
+    pub fn transfer_from(&mut self, from: AccountId, to: AccountId, value: u128) -> Result<()> {
+        Ok(IErc20::transfer_from(&mut self.inner, from, to, value)?)
+    }
+}
+
+#[cfg(feature = "psp22")]
+impl Token {
+    /// Returns the balance of `owner`.
+    pub fn balance_of(&self, owner: AccountId) -> u128 {
+        PSP22::balance_of(&self.inner, owner)
+    }
+
+    /// Transfers `value` tokens from the pool to `to`.
+    pub fn transfer(&mut self, to: AccountId, value: u128) -> Result<()> {
+        Ok(PSP22::transfer(&mut self.inner, to, value, Vec::new())?)
+    }
+
+    /// Transfers `value` tokens from `from` to `to`, out of the allowance of the pool.
+    pub fn transfer_from(&mut self, from: AccountId, to: AccountId, value: u128) -> Result<()> {
+        Ok(PSP22::transfer_from(
+            &mut self.inner,
+            from,
+            to,
+            value,
+            Vec::new(),
+        )?)
+    }
+}
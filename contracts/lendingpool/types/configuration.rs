@@ -0,0 +1,460 @@
+use super::{LendingError, Result};
+use ink_storage::traits::{PackedLayout, SpreadLayout};
+use raymath::{percent_mul, PERCENTAGE_FACTOR};
+
+const LTV_START_BIT_POSITION: u32 = 0;
+const LIQUIDATION_THRESHOLD_START_BIT_POSITION: u32 = 16;
+const LIQUIDATION_BONUS_START_BIT_POSITION: u32 = 32;
+const RESERVE_DECIMALS_START_BIT_POSITION: u32 = 48;
+const IS_ACTIVE_START_BIT_POSITION: u32 = 56;
+const IS_FROZEN_START_BIT_POSITION: u32 = 57;
+const BORROWING_ENABLED_START_BIT_POSITION: u32 = 58;
+const STABLE_BORROWING_ENABLED_START_BIT_POSITION: u32 = 59;
+const RESERVE_FACTOR_START_BIT_POSITION: u32 = 64;
+
+const BORROW_CAP_START_BIT_POSITION: u32 = 0;
+const SUPPLY_CAP_START_BIT_POSITION: u32 = 64;
+
+const PERCENTAGE_BITS: u32 = 16;
+const DECIMALS_BITS: u32 = 8;
+const CAP_BITS: u32 = 64;
+
+const MAX_VALID_LIQUIDATION_BONUS: u128 = 65535;
+/// Above 38 decimals a whole token unit no longer fits in a `u128`.
+const MAX_VALID_DECIMALS: u8 = 38;
+const MAX_VALID_CAP: u128 = u64::MAX as u128;
+
+/// Risk parameters of a reserve, packed in a single word:
+///
+/// - bits 0-15: loan to value, in basis points
+/// - bits 16-31: liquidation threshold, in basis points
+/// - bits 32-47: liquidation bonus, in basis points
+/// - bits 48-55: decimals of the underlying asset
+/// - bit 56: reserve is active
+/// - bit 57: reserve is frozen
+/// - bit 58: borrowing is enabled
+/// - bit 59: stable rate borrowing is enabled
+/// - bits 64-79: reserve factor, in basis points
+///
+/// and the caps in a second word, in whole tokens, 0 meaning no cap:
+///
+/// - bits 0-63: borrow cap
+/// - bits 64-127: supply cap
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Copy,
+    Clone,
+    scale::Encode,
+    scale::Decode,
+    SpreadLayout,
+    PackedLayout,
+)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+)]
+pub struct ReserveConfiguration {
+    data: u128,
+    caps: u128,
+}
+
+fn get_bits(word: u128, start: u32, bits: u32) -> u128 {
+    (word >> start) & ((1 << bits) - 1)
+}
+
+fn set_bits(word: u128, start: u32, bits: u32, value: u128) -> u128 {
+    let mask = ((1 << bits) - 1) << start;
+    (word & !mask) | (value << start)
+}
+
+impl ReserveConfiguration {
+    fn get(&self, start: u32, bits: u32) -> u128 {
+        get_bits(self.data, start, bits)
+    }
+
+    fn set(&mut self, start: u32, bits: u32, value: u128) {
+        self.data = set_bits(self.data, start, bits, value);
+    }
+
+    /// Maximum share of the collateral value that can be borrowed, in basis points.
+    pub fn ltv(&self) -> u128 {
+        self.get(LTV_START_BIT_POSITION, PERCENTAGE_BITS)
+    }
+
+    pub fn set_ltv(&mut self, ltv: u128) -> Result<()> {
+        if ltv > PERCENTAGE_FACTOR {
+            return Err(LendingError::RcInvalidLtv);
+        }
+        self.set(LTV_START_BIT_POSITION, PERCENTAGE_BITS, ltv);
+        Ok(())
+    }
+
+    /// Share of the collateral value at which a position can be liquidated, in basis points.
+    pub fn liquidation_threshold(&self) -> u128 {
+        self.get(LIQUIDATION_THRESHOLD_START_BIT_POSITION, PERCENTAGE_BITS)
+    }
+
+    pub fn set_liquidation_threshold(&mut self, threshold: u128) -> Result<()> {
+        if threshold > PERCENTAGE_FACTOR {
+            return Err(LendingError::RcInvalidLiqThreshold);
+        }
+        self.set(LIQUIDATION_THRESHOLD_START_BIT_POSITION, PERCENTAGE_BITS, threshold);
+        Ok(())
+    }
+
+    /// Percentage applied to the repaid debt to get the seized collateral, in basis points.
+    pub fn liquidation_bonus(&self) -> u128 {
+        self.get(LIQUIDATION_BONUS_START_BIT_POSITION, PERCENTAGE_BITS)
+    }
+
+    pub fn set_liquidation_bonus(&mut self, bonus: u128) -> Result<()> {
+        if bonus > MAX_VALID_LIQUIDATION_BONUS {
+            return Err(LendingError::RcInvalidLiqBonus);
+        }
+        self.set(LIQUIDATION_BONUS_START_BIT_POSITION, PERCENTAGE_BITS, bonus);
+        Ok(())
+    }
+
+    /// Decimals of the underlying asset.
+    pub fn decimals(&self) -> u8 {
+        self.get(RESERVE_DECIMALS_START_BIT_POSITION, DECIMALS_BITS) as u8
+    }
+
+    pub fn set_decimals(&mut self, decimals: u8) -> Result<()> {
+        if decimals > MAX_VALID_DECIMALS {
+            return Err(LendingError::RcInvalidDecimals);
+        }
+        self.set(RESERVE_DECIMALS_START_BIT_POSITION, DECIMALS_BITS, decimals as u128);
+        Ok(())
+    }
+
+    /// Whether the reserve accepts any action at all.
+    pub fn is_active(&self) -> bool {
+        self.get(IS_ACTIVE_START_BIT_POSITION, 1) != 0
+    }
+
+    pub fn set_active(&mut self, active: bool) {
+        self.set(IS_ACTIVE_START_BIT_POSITION, 1, active as u128);
+    }
+
+    /// Whether the reserve refuses new deposits and borrows, still allowing repays,
+    /// withdrawals and liquidations.
+    pub fn is_frozen(&self) -> bool {
+        self.get(IS_FROZEN_START_BIT_POSITION, 1) != 0
+    }
+
+    pub fn set_frozen(&mut self, frozen: bool) {
+        self.set(IS_FROZEN_START_BIT_POSITION, 1, frozen as u128);
+    }
+
+    /// Whether the reserve can be borrowed.
+    pub fn borrowing_enabled(&self) -> bool {
+        self.get(BORROWING_ENABLED_START_BIT_POSITION, 1) != 0
+    }
+
+    pub fn set_borrowing_enabled(&mut self, enabled: bool) {
+        self.set(BORROWING_ENABLED_START_BIT_POSITION, 1, enabled as u128);
+    }
+
+    /// Whether the reserve can be borrowed at a stable rate.
+    pub fn stable_borrowing_enabled(&self) -> bool {
+        self.get(STABLE_BORROWING_ENABLED_START_BIT_POSITION, 1) != 0
+    }
+
+    pub fn set_stable_borrowing_enabled(&mut self, enabled: bool) {
+        self.set(STABLE_BORROWING_ENABLED_START_BIT_POSITION, 1, enabled as u128);
+    }
+
+    /// Share of the borrow interest not distributed to the depositors, in basis points.
+    pub fn reserve_factor(&self) -> u128 {
+        self.get(RESERVE_FACTOR_START_BIT_POSITION, PERCENTAGE_BITS)
+    }
+
+    pub fn set_reserve_factor(&mut self, reserve_factor: u128) -> Result<()> {
+        if reserve_factor > PERCENTAGE_FACTOR {
+            return Err(LendingError::RcInvalidReserveFactor);
+        }
+        self.set(RESERVE_FACTOR_START_BIT_POSITION, PERCENTAGE_BITS, reserve_factor);
+        Ok(())
+    }
+
+    /// Maximum debt of the reserve, in whole tokens, 0 meaning no cap.
+    pub fn borrow_cap(&self) -> u128 {
+        get_bits(self.caps, BORROW_CAP_START_BIT_POSITION, CAP_BITS)
+    }
+
+    pub fn set_borrow_cap(&mut self, borrow_cap: u128) -> Result<()> {
+        if borrow_cap > MAX_VALID_CAP {
+            return Err(LendingError::RcInvalidBorrowCap);
+        }
+        self.caps = set_bits(self.caps, BORROW_CAP_START_BIT_POSITION, CAP_BITS, borrow_cap);
+        Ok(())
+    }
+
+    /// Maximum sToken supply of the reserve, in whole tokens, 0 meaning no cap.
+    pub fn supply_cap(&self) -> u128 {
+        get_bits(self.caps, SUPPLY_CAP_START_BIT_POSITION, CAP_BITS)
+    }
+
+    pub fn set_supply_cap(&mut self, supply_cap: u128) -> Result<()> {
+        if supply_cap > MAX_VALID_CAP {
+            return Err(LendingError::RcInvalidSupplyCap);
+        }
+        self.caps = set_bits(self.caps, SUPPLY_CAP_START_BIT_POSITION, CAP_BITS, supply_cap);
+        Ok(())
+    }
+
+    /// Whether a `total_debt` of the reserve, in the smallest unit of the asset, is above the
+    /// borrow cap.
+    pub fn exceeds_borrow_cap(&self, total_debt: u128) -> bool {
+        self.exceeds_cap(total_debt, self.borrow_cap())
+    }
+
+    /// Whether a `total_supply` of the reserve, in the smallest unit of the asset, is above the
+    /// supply cap.
+    pub fn exceeds_supply_cap(&self, total_supply: u128) -> bool {
+        self.exceeds_cap(total_supply, self.supply_cap())
+    }
+
+    fn exceeds_cap(&self, amount: u128, cap: u128) -> bool {
+        if cap == 0 {
+            return false;
+        }
+        // a cap too large to express in the smallest unit can't be reached
+        match cap.checked_mul(10u128.pow(self.decimals() as u32)) {
+            Some(cap) => amount > cap,
+            None => false,
+        }
+    }
+
+    /// Sets the parameters of the reserve as collateral, checking they are consistent:
+    /// the ltv is below the liquidation threshold, and a liquidated position keeps
+    /// enough collateral to pay the liquidation bonus.
+    ///
+    /// A zero liquidation threshold disables the reserve as collateral.
+    pub fn set_collateral_params(
+        &mut self,
+        ltv: u128,
+        liquidation_threshold: u128,
+        liquidation_bonus: u128,
+    ) -> Result<()> {
+        if ltv > liquidation_threshold {
+            return Err(LendingError::LpcInvalidConfiguration);
+        }
+        if liquidation_threshold != 0 {
+            if liquidation_bonus <= PERCENTAGE_FACTOR
+                || percent_mul(liquidation_threshold, liquidation_bonus)? > PERCENTAGE_FACTOR
+            {
+                return Err(LendingError::LpcInvalidConfiguration);
+            }
+        } else if liquidation_bonus != 0 {
+            return Err(LendingError::LpcInvalidConfiguration);
+        }
+        self.set_ltv(ltv)?;
+        self.set_liquidation_threshold(liquidation_threshold)?;
+        self.set_liquidation_bonus(liquidation_bonus)
+    }
+
+    /// Returns `(ltv, liquidation_threshold, liquidation_bonus, decimals, reserve_factor)`.
+    pub fn get_params(&self) -> (u128, u128, u128, u8, u128) {
+        (
+            self.ltv(),
+            self.liquidation_threshold(),
+            self.liquidation_bonus(),
+            self.decimals(),
+            self.reserve_factor(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every field at its largest valid value and every flag set.
+    fn full() -> ReserveConfiguration {
+        let mut configuration = ReserveConfiguration::default();
+        configuration.set_ltv(PERCENTAGE_FACTOR).unwrap();
+        configuration
+            .set_liquidation_threshold(PERCENTAGE_FACTOR)
+            .unwrap();
+        configuration
+            .set_liquidation_bonus(MAX_VALID_LIQUIDATION_BONUS)
+            .unwrap();
+        configuration.set_decimals(MAX_VALID_DECIMALS).unwrap();
+        configuration.set_active(true);
+        configuration.set_frozen(true);
+        configuration.set_borrowing_enabled(true);
+        configuration.set_stable_borrowing_enabled(true);
+        configuration.set_reserve_factor(PERCENTAGE_FACTOR).unwrap();
+        configuration.set_borrow_cap(MAX_VALID_CAP).unwrap();
+        configuration.set_supply_cap(MAX_VALID_CAP).unwrap();
+        configuration
+    }
+
+    fn fields(configuration: &ReserveConfiguration) -> [u128; 11] {
+        [
+            configuration.ltv(),
+            configuration.liquidation_threshold(),
+            configuration.liquidation_bonus(),
+            configuration.decimals() as u128,
+            configuration.is_active() as u128,
+            configuration.is_frozen() as u128,
+            configuration.borrowing_enabled() as u128,
+            configuration.stable_borrowing_enabled() as u128,
+            configuration.reserve_factor(),
+            configuration.borrow_cap(),
+            configuration.supply_cap(),
+        ]
+    }
+
+    #[test]
+    fn fields_round_trip_at_their_max_value() {
+        assert_eq!(
+            fields(&full()),
+            [
+                PERCENTAGE_FACTOR,
+                PERCENTAGE_FACTOR,
+                MAX_VALID_LIQUIDATION_BONUS,
+                MAX_VALID_DECIMALS as u128,
+                1,
+                1,
+                1,
+                1,
+                PERCENTAGE_FACTOR,
+                MAX_VALID_CAP,
+                MAX_VALID_CAP,
+            ]
+        );
+    }
+
+    #[test]
+    fn setting_a_field_leaves_the_others_untouched() {
+        // each setter, in the order of `fields`, clearing then setting its field
+        let setters: [fn(&mut ReserveConfiguration, bool); 11] = [
+            |c, on| c.set_ltv(on as u128 * PERCENTAGE_FACTOR).unwrap(),
+            |c, on| {
+                c.set_liquidation_threshold(on as u128 * PERCENTAGE_FACTOR)
+                    .unwrap()
+            },
+            |c, on| {
+                c.set_liquidation_bonus(on as u128 * MAX_VALID_LIQUIDATION_BONUS)
+                    .unwrap()
+            },
+            |c, on| c.set_decimals(on as u8 * MAX_VALID_DECIMALS).unwrap(),
+            |c, on| c.set_active(on),
+            |c, on| c.set_frozen(on),
+            |c, on| c.set_borrowing_enabled(on),
+            |c, on| c.set_stable_borrowing_enabled(on),
+            |c, on| {
+                c.set_reserve_factor(on as u128 * PERCENTAGE_FACTOR)
+                    .unwrap()
+            },
+            |c, on| c.set_borrow_cap(on as u128 * MAX_VALID_CAP).unwrap(),
+            |c, on| c.set_supply_cap(on as u128 * MAX_VALID_CAP).unwrap(),
+        ];
+        let max = fields(&full());
+
+        for (i, setter) in setters.iter().enumerate() {
+            let mut configuration = full();
+            setter(&mut configuration, false);
+            let mut expected = max;
+            expected[i] = 0;
+            assert_eq!(fields(&configuration), expected, "clearing field {}", i);
+
+            let mut configuration = ReserveConfiguration::default();
+            setter(&mut configuration, true);
+            let mut expected = [0; 11];
+            expected[i] = max[i];
+            assert_eq!(fields(&configuration), expected, "setting field {}", i);
+        }
+    }
+
+    #[test]
+    fn setters_reject_values_above_their_max() {
+        let mut configuration = full();
+        assert_eq!(
+            configuration.set_ltv(PERCENTAGE_FACTOR + 1),
+            Err(LendingError::RcInvalidLtv)
+        );
+        assert_eq!(
+            configuration.set_liquidation_threshold(PERCENTAGE_FACTOR + 1),
+            Err(LendingError::RcInvalidLiqThreshold)
+        );
+        assert_eq!(
+            configuration.set_liquidation_bonus(MAX_VALID_LIQUIDATION_BONUS + 1),
+            Err(LendingError::RcInvalidLiqBonus)
+        );
+        assert_eq!(
+            configuration.set_decimals(MAX_VALID_DECIMALS + 1),
+            Err(LendingError::RcInvalidDecimals)
+        );
+        assert_eq!(
+            configuration.set_reserve_factor(PERCENTAGE_FACTOR + 1),
+            Err(LendingError::RcInvalidReserveFactor)
+        );
+        assert_eq!(
+            configuration.set_borrow_cap(MAX_VALID_CAP + 1),
+            Err(LendingError::RcInvalidBorrowCap)
+        );
+        assert_eq!(
+            configuration.set_supply_cap(MAX_VALID_CAP + 1),
+            Err(LendingError::RcInvalidSupplyCap)
+        );
+        assert_eq!(configuration, full());
+    }
+
+    #[test]
+    fn collateral_params_must_be_consistent() {
+        let mut configuration = ReserveConfiguration::default();
+        assert_eq!(
+            configuration.set_collateral_params(7_500, 8_000, 10_500),
+            Ok(())
+        );
+        assert_eq!(
+            (
+                configuration.ltv(),
+                configuration.liquidation_threshold(),
+                configuration.liquidation_bonus()
+            ),
+            (7_500, 8_000, 10_500)
+        );
+
+        let invalid = Err(LendingError::LpcInvalidConfiguration);
+        // ltv above the threshold
+        assert_eq!(
+            configuration.set_collateral_params(8_500, 8_000, 10_500),
+            invalid
+        );
+        // no bonus for the liquidators
+        assert_eq!(
+            configuration.set_collateral_params(7_500, 8_000, 10_000),
+            invalid
+        );
+        // not enough collateral left to pay the bonus
+        assert_eq!(
+            configuration.set_collateral_params(7_500, 9_800, 10_500),
+            invalid
+        );
+        // a bonus on a reserve that is not a collateral
+        assert_eq!(configuration.set_collateral_params(0, 0, 10_500), invalid);
+        assert_eq!(configuration.set_collateral_params(0, 0, 0), Ok(()));
+    }
+
+    #[test]
+    fn caps_scale_with_the_decimals() {
+        let mut configuration = ReserveConfiguration::default();
+        configuration.set_decimals(2).unwrap();
+        assert!(!configuration.exceeds_borrow_cap(u128::MAX));
+
+        configuration.set_borrow_cap(10).unwrap();
+        configuration.set_supply_cap(MAX_VALID_CAP).unwrap();
+        assert!(!configuration.exceeds_borrow_cap(1_000));
+        assert!(configuration.exceeds_borrow_cap(1_001));
+        assert!(!configuration.exceeds_supply_cap(MAX_VALID_CAP * 100));
+        assert!(configuration.exceeds_supply_cap(MAX_VALID_CAP * 100 + 1));
+    }
+}
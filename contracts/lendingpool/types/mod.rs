@@ -1,10 +1,57 @@
+mod configuration;
 #[allow(unused)]
 mod errors;
+mod user_configuration;
 
+pub use configuration::*;
 pub use errors::*;
+pub use user_configuration::*;
 
+use crate::interest_rate::DefaultInterestRateStrategy;
 use ink_env::AccountId;
 use ink_storage::traits::{PackedLayout, SpreadLayout};
+use raymath::*;
+
+/// Maximum share of a user's debt that can be repaid in one liquidation, in basis points.
+pub const LIQUIDATION_CLOSE_FACTOR_PERCENT: u128 = 5000;
+
+/// Below this health factor (in wad) a position can be liquidated.
+pub const HEALTH_FACTOR_LIQUIDATION_THRESHOLD: u128 = 1_000_000_000_000_000_000;
+
+/// Premium charged on flash loans that are paid back, in basis points.
+pub const FLASHLOAN_PREMIUM_TOTAL: u128 = 9;
+
+/// Largest share of the available liquidity a single stable rate borrow can take, in basis points.
+pub const MAX_STABLE_RATE_BORROW_SIZE_PERCENT: u128 = 2500;
+
+/// Utilisation above which stable borrowers paying less than the depositors earn can be
+/// rebalanced to the current stable rate, in ray.
+pub const REBALANCE_UP_USAGE_RATIO_THRESHOLD: u128 = 950_000_000_000_000_000_000_000_000;
+
+/// Default age in milliseconds after which an oracle price is considered stale.
+pub const DEFAULT_MAX_PRICE_AGE: u64 = 3600 * 1000;
+
+/// Accounts allowed to configure the pool, each held by a single account.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum Role {
+    /// Lists reserves, manages the oracles and hands out the roles.
+    PoolAdmin,
+    /// Tunes the rates and risk parameters of the reserves.
+    RiskAdmin,
+    /// Pauses the pool.
+    EmergencyAdmin,
+}
+
+/// Rate at which a debt accrues interest.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum InterestRateMode {
+    /// Rate fixed for the borrower when borrowing, until rebalanced.
+    Stable = 1,
+    /// Rate following the utilisation of the reserve.
+    Variable = 2,
+}
 
 /// refer to the whitepaper, section 1.1 basic concepts for a formal description of these properties.
 #[derive(
@@ -15,27 +62,160 @@ use ink_storage::traits::{PackedLayout, SpreadLayout};
     derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
 )]
 pub struct ReserveData {
-    // stable liquidity rate at which the user has deposited.
-    pub stable_liquidity_rate: u128,
-    // stable borrow rate at which the user has borrowed.
-    pub stable_borrow_rate: u128,
+    // cumulated liquidity interest of the reserve since its listing, in ray.
+    pub liquidity_index: u128,
+    // cumulated variable borrow interest of the reserve since its listing, in ray.
+    pub variable_borrow_index: u128,
+    // current yearly rate earned by the depositors, in ray.
+    pub current_liquidity_rate: u128,
+    // current yearly rate paid by the variable borrowers, in ray.
+    pub current_variable_borrow_rate: u128,
+    // yearly rate granted to new stable borrows, in ray.
+    pub current_stable_borrow_rate: u128,
+    pub last_update_timestamp: u64,
+    // reserve factor share of the borrow interest not yet minted to the treasury, in sTokens
+    // divided by the liquidity index.
+    pub accrued_to_treasury: u128,
+
+    // model computing the rates above from the utilisation of the reserve.
+    pub interest_rate_strategy: DefaultInterestRateStrategy,
+
+    // risk parameters of the reserve.
+    pub configuration: ReserveConfiguration,
 
     pub stoken_address: AccountId,
 
     pub stable_debt_token_address: AccountId,
+
+    pub variable_debt_token_address: AccountId,
+
+    // position of the reserve in the reserves list, and of its bits in the user configurations.
+    pub id: u8,
 }
 
-#[derive(
-    Debug, Default, PartialEq, Eq, Clone, scale::Encode, scale::Decode, SpreadLayout, PackedLayout,
-)]
-#[cfg_attr(
-    feature = "std",
-    derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
-)]
+impl ReserveData {
+    /// Accrues the interest of the reserve up to `now` into the liquidity and borrow indexes.
+    pub fn update_state(&mut self, now: u64) -> Result<()> {
+        if self.last_update_timestamp == now {
+            return Ok(());
+        }
+        self.liquidity_index = self.normalized_income(now)?;
+        self.variable_borrow_index = self.normalized_variable_debt(now)?;
+        self.last_update_timestamp = now;
+        Ok(())
+    }
+
+    /// Distributes `amount` to the depositors of the reserve, currently holding `total_liquidity`.
+    pub fn cumulate_to_liquidity_index(
+        &mut self,
+        total_liquidity: u128,
+        amount: u128,
+    ) -> Result<()> {
+        if total_liquidity == 0 || amount == 0 {
+            return Ok(());
+        }
+        let cumulated = ray_div(amount, total_liquidity)?
+            .checked_add(RAY)
+            .ok_or(LendingError::RlLiquidityIndexOverflow)?;
+        self.liquidity_index = ray_mul(cumulated, self.liquidity_index)
+            .map_err(|_| LendingError::RlLiquidityIndexOverflow)?;
+        Ok(())
+    }
+
+    /// The liquidity index at `now`, by which sToken scaled balances are multiplied.
+    pub fn normalized_income(&self, now: u64) -> Result<u128> {
+        if self.last_update_timestamp == now || self.current_liquidity_rate == 0 {
+            return Ok(self.liquidity_index);
+        }
+        let cumulated = calculate_linear_interest(
+            self.current_liquidity_rate,
+            self.last_update_timestamp,
+            now,
+        )?;
+        ray_mul(cumulated, self.liquidity_index)
+            .map_err(|_| LendingError::RlLiquidityIndexOverflow)
+    }
+
+    /// The variable borrow index at `now`, by which variable debt token scaled balances are
+    /// multiplied.
+    pub fn normalized_variable_debt(&self, now: u64) -> Result<u128> {
+        if self.last_update_timestamp == now || self.current_variable_borrow_rate == 0 {
+            return Ok(self.variable_borrow_index);
+        }
+        let cumulated = calculate_compounded_interest(
+            self.current_variable_borrow_rate,
+            self.last_update_timestamp,
+            now,
+        )?;
+        ray_mul(cumulated, self.variable_borrow_index)
+            .map_err(|_| LendingError::RlVariableBorrowIndexOverflow)
+    }
+}
+
+/// Parameters of a reserve listed through `init_reserve`.
+#[derive(Debug, Default, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct InitReserveParams {
+    pub decimals: u8,
+    pub interest_rate_strategy: DefaultInterestRateStrategy,
+    pub stable_borrow_rate_enabled: bool,
+    // in basis points.
+    pub reserve_factor: u128,
+    pub ltv: u128,
+    pub liquidation_threshold: u128,
+    pub liquidation_bonus: u128,
+    // in whole tokens, 0 meaning no cap.
+    pub borrow_cap: u128,
+    pub supply_cap: u128,
+}
+
+/// Liquidity and debt of a reserve, interest included.
+#[derive(Debug, Default, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct ReserveLiquidityData {
+    // underlying asset held by the pool, available to borrow and withdraw.
+    pub available_liquidity: u128,
+    pub total_stable_debt: u128,
+    pub total_variable_debt: u128,
+    // average rate of the stable debt, in ray.
+    pub average_stable_rate: u128,
+    // share of the available liquidity plus the debt that is borrowed, in ray.
+    pub utilization_rate: u128,
+}
+
+/// Position of a user in a single reserve.
+#[derive(Debug, Default, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
 pub struct UserReserveData {
-    pub cumulated_liquidity_interest: u128,
-    pub cumulated_stable_borrow_interest: u128,
-    pub last_update_timestamp: u64,
-    // amount borrowed by the user.
-    pub borrow_balance: u128,
+    // sToken balance, including the accrued liquidity interest.
+    pub current_stoken_balance: u128,
+    // sToken balance as stored on the token, divided by the liquidity index.
+    pub scaled_stoken_balance: u128,
+    // stable debt, including the accrued interest.
+    pub current_stable_debt: u128,
+    // stable debt as stored on the stable debt token, at the last action of the user.
+    pub principal_stable_debt: u128,
+    // stable rate of the user debt, in ray.
+    pub stable_borrow_rate: u128,
+    // variable debt, including the accrued interest.
+    pub current_variable_debt: u128,
+    // variable debt as stored on the variable debt token, divided by the variable borrow index.
+    pub scaled_variable_debt: u128,
+}
+
+/// Aggregated position of a user over all the reserves.
+#[derive(Debug, Default, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct UserAccountData {
+    // valued in the base currency of the price oracle, in wad.
+    pub total_collateral: u128,
+    pub total_debt: u128,
+    // collateral times the loan to value, minus the debt.
+    pub available_borrows: u128,
+    // collateral weighted average of the reserves liquidation thresholds, in basis points.
+    pub current_liquidation_threshold: u128,
+    // collateral weighted average of the reserves loan to values, in basis points.
+    pub ltv: u128,
+    // in wad, `u128::MAX` when the user has no debt.
+    pub health_factor: u128,
 }
@@ -0,0 +1,129 @@
+use ink_storage::traits::{PackedLayout, SpreadLayout};
+
+/// Number of reserves a user configuration can track, two bits each.
+pub const MAX_NUMBER_RESERVES: u32 = 64;
+
+/// Reserves a user borrows or uses as collateral, packed in a single word.
+///
+/// The reserve with id `n` takes two bits:
+///
+/// - bit `2n`: the user borrows the reserve
+/// - bit `2n + 1`: the user uses their deposit in the reserve as collateral
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Copy,
+    Clone,
+    scale::Encode,
+    scale::Decode,
+    SpreadLayout,
+    PackedLayout,
+)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+)]
+pub struct UserConfiguration {
+    data: u128,
+}
+
+impl UserConfiguration {
+    fn borrowing_bit(reserve_id: u8) -> u128 {
+        1 << (reserve_id as u32 * 2)
+    }
+
+    fn collateral_bit(reserve_id: u8) -> u128 {
+        1 << (reserve_id as u32 * 2 + 1)
+    }
+
+    /// Whether the user has debt in the reserve.
+    pub fn is_borrowing(&self, reserve_id: u8) -> bool {
+        self.data & Self::borrowing_bit(reserve_id) != 0
+    }
+
+    pub fn set_borrowing(&mut self, reserve_id: u8, borrowing: bool) {
+        if borrowing {
+            self.data |= Self::borrowing_bit(reserve_id);
+        } else {
+            self.data &= !Self::borrowing_bit(reserve_id);
+        }
+    }
+
+    /// Whether the deposit of the user in the reserve backs their debt.
+    pub fn is_using_as_collateral(&self, reserve_id: u8) -> bool {
+        self.data & Self::collateral_bit(reserve_id) != 0
+    }
+
+    pub fn set_using_as_collateral(&mut self, reserve_id: u8, using_as_collateral: bool) {
+        if using_as_collateral {
+            self.data |= Self::collateral_bit(reserve_id);
+        } else {
+            self.data &= !Self::collateral_bit(reserve_id);
+        }
+    }
+
+    /// Whether the reserve counts in the account data of the user.
+    pub fn is_using_as_collateral_or_borrowing(&self, reserve_id: u8) -> bool {
+        self.is_borrowing(reserve_id) || self.is_using_as_collateral(reserve_id)
+    }
+
+    /// Whether the user has debt in any reserve.
+    pub fn is_borrowing_any(&self) -> bool {
+        self.data & 0x5555_5555_5555_5555_5555_5555_5555_5555 != 0
+    }
+
+    /// Whether the user neither borrows nor uses any reserve as collateral.
+    pub fn is_empty(&self) -> bool {
+        self.data == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_and_last_reserves_use_their_own_bits() {
+        let last = (MAX_NUMBER_RESERVES - 1) as u8;
+        let mut configuration = UserConfiguration::default();
+        assert!(configuration.is_empty());
+
+        configuration.set_borrowing(last, true);
+        configuration.set_using_as_collateral(0, true);
+        assert!(configuration.is_borrowing(last));
+        assert!(!configuration.is_using_as_collateral(last));
+        assert!(!configuration.is_borrowing(last - 1));
+        assert!(configuration.is_using_as_collateral(0));
+        assert!(!configuration.is_borrowing(0));
+        assert!(!configuration.is_using_as_collateral(1));
+        assert!(configuration.is_using_as_collateral_or_borrowing(0));
+        assert!(configuration.is_using_as_collateral_or_borrowing(last));
+        assert!(configuration.is_borrowing_any());
+
+        configuration.set_using_as_collateral(last, true);
+        configuration.set_borrowing(last, false);
+        assert!(configuration.is_using_as_collateral(last));
+        assert!(!configuration.is_borrowing_any());
+
+        configuration.set_using_as_collateral(last, false);
+        configuration.set_using_as_collateral(0, false);
+        assert!(configuration.is_empty());
+    }
+
+    #[test]
+    fn borrowing_any_only_looks_at_the_borrowing_bits() {
+        let mut configuration = UserConfiguration::default();
+        for reserve_id in 0..MAX_NUMBER_RESERVES as u8 {
+            configuration.set_using_as_collateral(reserve_id, true);
+        }
+        assert!(!configuration.is_borrowing_any());
+
+        configuration.set_borrowing(0, true);
+        assert!(configuration.is_borrowing_any());
+        configuration.set_borrowing(0, false);
+        configuration.set_borrowing((MAX_NUMBER_RESERVES - 1) as u8, true);
+        assert!(configuration.is_borrowing_any());
+    }
+}
@@ -0,0 +1,387 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::variabledebttoken::VariableDebtToken;
+use ink_lang as ink;
+
+/// Debt of the variable rate borrowers of a reserve.
+///
+/// Balances are stored divided by the variable borrow index of the reserve at the time of
+/// each action, so multiplying them by the current index makes them grow with the interest
+/// owed. The token can't be transferred nor approved, only the lending pool mints and burns
+/// it. Instead, a user can delegate credit to another account, which can then borrow on
+/// their behalf up to the delegated amount.
+#[ink::contract]
+mod variabledebttoken {
+    use ink_env::call::FromAccountId;
+    use ink_prelude::string::String;
+    use interfaces::{Callee, LendingError, PoolCallbacks};
+    use raymath::{ray_div, ray_mul, MathError};
+
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::collections::HashMap as StorageHashMap;
+
+    /// The variable debt token error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller is not the lending pool.
+        CallerMustBeLendingPool,
+        /// Returned if the borrower was not delegated enough credit by the debt holder.
+        BorrowAllowanceNotEnough,
+        /// Returned if the amount to burn is above the debt of the user.
+        InsufficientBalance,
+        /// Returned if the amount to mint is 0 once scaled.
+        InvalidMintAmount,
+        /// Returned if the amount to burn is 0 once scaled.
+        InvalidBurnAmount,
+        /// Returned on transfers and approvals, debt can't be moved.
+        OperationNotSupported,
+        /// Returned if a balance overflows.
+        Overflow,
+    }
+
+    /// The variable debt token result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl From<MathError> for Error {
+        fn from(_: MathError) -> Self {
+            Error::Overflow
+        }
+    }
+
+    impl From<Error> for LendingError {
+        fn from(error: Error) -> Self {
+            match error {
+                Error::CallerMustBeLendingPool => LendingError::CtCallerMustBeLendingPool,
+                Error::BorrowAllowanceNotEnough => LendingError::CtBorrowAllowanceNotEnough,
+                Error::InsufficientBalance => LendingError::Erc20InsufficientBalance,
+                Error::InvalidMintAmount => LendingError::CtInvalidMintAmount,
+                Error::InvalidBurnAmount => LendingError::CtInvalidBurnAmount,
+                Error::OperationNotSupported => LendingError::VlTransferNotAllowed,
+                Error::Overflow => LendingError::MathMultiplicationOverflow,
+            }
+        }
+    }
+
+    /// Event emitted when new variable debt is minted.
+    #[ink(event)]
+    pub struct Mint {
+        /// The account receiving the borrowed funds.
+        #[ink(topic)]
+        user: AccountId,
+        /// The account taking the debt.
+        #[ink(topic)]
+        on_behalf_of: AccountId,
+        amount: Balance,
+        /// Variable borrow index the amount was scaled by.
+        index: u128,
+    }
+
+    /// Event emitted when variable debt is burnt.
+    #[ink(event)]
+    pub struct Burn {
+        #[ink(topic)]
+        user: AccountId,
+        amount: Balance,
+        /// Variable borrow index the amount was scaled by.
+        index: u128,
+    }
+
+    /// Event emitted when `from_user` lets `to_user` borrow up to `amount` on their behalf.
+    #[ink(event)]
+    pub struct BorrowAllowanceDelegated {
+        #[ink(topic)]
+        from_user: AccountId,
+        #[ink(topic)]
+        to_user: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(storage)]
+    pub struct VariableDebtToken {
+        /// The lending pool, the only account allowed to mint and burn.
+        pool: AccountId,
+        /// The asset borrowed.
+        underlying_asset: AccountId,
+        name: String,
+        symbol: String,
+        decimals: u8,
+        /// Mapping from user to their debt divided by the variable borrow index.
+        scaled_balances: StorageHashMap<AccountId, Balance>,
+        /// Sum of the scaled balances.
+        scaled_total_supply: Balance,
+        /// Mapping from (delegator, delegatee) to the amount the delegatee can still borrow
+        /// on behalf of the delegator.
+        borrow_allowances: StorageHashMap<(AccountId, AccountId), Balance>,
+    }
+
+    impl VariableDebtToken {
+        #[ink(constructor)]
+        pub fn new(
+            pool: AccountId,
+            underlying_asset: AccountId,
+            name: String,
+            symbol: String,
+            decimals: u8,
+        ) -> Self {
+            Self {
+                pool,
+                underlying_asset,
+                name,
+                symbol,
+                decimals,
+                scaled_balances: StorageHashMap::new(),
+                scaled_total_supply: 0,
+                borrow_allowances: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the token name.
+        #[ink(message)]
+        pub fn token_name(&self) -> String {
+            self.name.clone()
+        }
+
+        /// Returns the token symbol.
+        #[ink(message)]
+        pub fn token_symbol(&self) -> String {
+            self.symbol.clone()
+        }
+
+        /// Returns the token decimals, the ones of the underlying asset.
+        #[ink(message)]
+        pub fn token_decimals(&self) -> u8 {
+            self.decimals
+        }
+
+        /// Returns the asset borrowed.
+        #[ink(message)]
+        pub fn underlying_asset(&self) -> AccountId {
+            self.underlying_asset
+        }
+
+        /// Returns the lending pool.
+        #[ink(message)]
+        pub fn pool(&self) -> AccountId {
+            self.pool
+        }
+
+        /// Returns the debt of `user`, including the interest accrued since.
+        ///
+        /// Queries the variable borrow index from the pool, so the pool itself must use
+        /// `scaled_balance_of` instead.
+        #[ink(message)]
+        pub fn balance_of(&self, user: AccountId) -> Balance {
+            ray_mul(
+                self.scaled_balance_of(user),
+                self.normalized_variable_debt(),
+            )
+            .unwrap_or(Balance::MAX)
+        }
+
+        /// Returns the debt of all the users, including the interest accrued since.
+        #[ink(message)]
+        pub fn total_supply(&self) -> Balance {
+            ray_mul(self.scaled_total_supply, self.normalized_variable_debt())
+                .unwrap_or(Balance::MAX)
+        }
+
+        /// Returns the debt of `user` divided by the variable borrow index, as stored on the token.
+        #[ink(message)]
+        pub fn scaled_balance_of(&self, user: AccountId) -> Balance {
+            self.scaled_balances.get(&user).copied().unwrap_or(0)
+        }
+
+        /// Returns the total supply divided by the variable borrow index, as stored on the token.
+        #[ink(message)]
+        pub fn scaled_total_supply(&self) -> Balance {
+            self.scaled_total_supply
+        }
+
+        /// Lets `delegatee` borrow up to `amount` on behalf of the caller, who takes the debt.
+        ///
+        /// If this function is called again it overwrites the current allowance with `amount`.
+        ///
+        /// A `BorrowAllowanceDelegated` event is emitted.
+        #[ink(message)]
+        pub fn approve_delegation(&mut self, delegatee: AccountId, amount: Balance) -> Result<()> {
+            let delegator = self.env().caller();
+            self.borrow_allowances
+                .insert((delegator, delegatee), amount);
+            self.env().emit_event(BorrowAllowanceDelegated {
+                from_user: delegator,
+                to_user: delegatee,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Returns the amount `to_user` can still borrow on behalf of `from_user`.
+        #[ink(message)]
+        pub fn borrow_allowance(&self, from_user: AccountId, to_user: AccountId) -> Balance {
+            self.borrow_allowances
+                .get(&(from_user, to_user))
+                .copied()
+                .unwrap_or(0)
+        }
+
+        /// Always fails, debt can't be transferred.
+        #[ink(message)]
+        pub fn transfer(&mut self, _to: AccountId, _value: Balance) -> Result<()> {
+            Err(Error::OperationNotSupported)
+        }
+
+        /// Always fails, debt can't be transferred.
+        #[ink(message)]
+        pub fn transfer_from(
+            &mut self,
+            _from: AccountId,
+            _to: AccountId,
+            _value: Balance,
+        ) -> Result<()> {
+            Err(Error::OperationNotSupported)
+        }
+
+        /// Always fails, use `approve_delegation` to let another account borrow on your behalf.
+        #[ink(message)]
+        pub fn approve(&mut self, _spender: AccountId, _value: Balance) -> Result<()> {
+            Err(Error::OperationNotSupported)
+        }
+
+        /// Always returns `0`, debt can't be transferred.
+        #[ink(message)]
+        pub fn allowance(&self, _owner: AccountId, _spender: AccountId) -> Balance {
+            0
+        }
+
+        /// Adds `amount` of debt to `on_behalf_of`, scaled by the variable borrow `index` of the
+        /// reserve, for funds borrowed by `user`.
+        ///
+        /// When `user` borrows on behalf of someone else, their borrow allowance decreases
+        /// by `amount`.
+        ///
+        /// On success a `Mint` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `CallerMustBeLendingPool` error if the caller is not the pool.
+        ///
+        /// Returns `BorrowAllowanceNotEnough` error if `user` was not delegated enough credit.
+        ///
+        /// Returns `InvalidMintAmount` error if `amount` is 0 once scaled.
+        #[ink(message)]
+        pub fn mint(
+            &mut self,
+            user: AccountId,
+            on_behalf_of: AccountId,
+            amount: Balance,
+            index: u128,
+        ) -> Result<()> {
+            self.only_pool()?;
+            if user != on_behalf_of {
+                self.decrease_borrow_allowance(on_behalf_of, user, amount)?;
+            }
+            let amount_scaled = ray_div(amount, index)?;
+            if amount_scaled == 0 {
+                return Err(Error::InvalidMintAmount);
+            }
+            let user_balance = self
+                .scaled_balance_of(on_behalf_of)
+                .checked_add(amount_scaled)
+                .ok_or(Error::Overflow)?;
+            self.scaled_total_supply = self
+                .scaled_total_supply
+                .checked_add(amount_scaled)
+                .ok_or(Error::Overflow)?;
+            self.scaled_balances.insert(on_behalf_of, user_balance);
+
+            self.env().emit_event(Mint {
+                user,
+                on_behalf_of,
+                amount,
+                index,
+            });
+            Ok(())
+        }
+
+        /// Removes `amount` of debt from `user`, scaled by the variable borrow `index` of the
+        /// reserve.
+        ///
+        /// Burning the whole debt of `user` at `index` clears it, whatever the rounding.
+        ///
+        /// On success a `Burn` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `CallerMustBeLendingPool` error if the caller is not the pool.
+        ///
+        /// Returns `InsufficientBalance` error if `amount` is above the debt of `user`.
+        ///
+        /// Returns `InvalidBurnAmount` error if `amount` is 0 once scaled.
+        #[ink(message)]
+        pub fn burn(&mut self, user: AccountId, amount: Balance, index: u128) -> Result<()> {
+            self.only_pool()?;
+            let scaled_balance = self.scaled_balance_of(user);
+            let balance = ray_mul(scaled_balance, index)?;
+            if amount > balance {
+                return Err(Error::InsufficientBalance);
+            }
+            let amount_scaled = if amount == balance {
+                scaled_balance
+            } else {
+                core::cmp::min(ray_div(amount, index)?, scaled_balance)
+            };
+            if amount_scaled == 0 {
+                return Err(Error::InvalidBurnAmount);
+            }
+            self.scaled_total_supply = self.scaled_total_supply.saturating_sub(amount_scaled);
+            if amount_scaled == scaled_balance {
+                self.scaled_balances.take(&user);
+            } else {
+                self.scaled_balances
+                    .insert(user, scaled_balance - amount_scaled);
+            }
+
+            self.env().emit_event(Burn {
+                user,
+                amount,
+                index,
+            });
+            Ok(())
+        }
+
+        fn only_pool(&self) -> Result<()> {
+            if self.env().caller() != self.pool {
+                return Err(Error::CallerMustBeLendingPool);
+            }
+            Ok(())
+        }
+
+        fn decrease_borrow_allowance(
+            &mut self,
+            delegator: AccountId,
+            delegatee: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            let allowance = self.borrow_allowance(delegator, delegatee);
+            if amount > allowance {
+                return Err(Error::BorrowAllowanceNotEnough);
+            }
+            let new_allowance = allowance - amount;
+            self.borrow_allowances
+                .insert((delegator, delegatee), new_allowance);
+            self.env().emit_event(BorrowAllowanceDelegated {
+                from_user: delegator,
+                to_user: delegatee,
+                amount: new_allowance,
+            });
+            Ok(())
+        }
+
+        /// Returns the variable borrow index of the reserve at the current block, from the pool.
+        fn normalized_variable_debt(&self) -> u128 {
+            let pool: Callee = FromAccountId::from_account_id(self.pool);
+            pool.get_reserve_normalized_variable_debt(self.underlying_asset)
+        }
+    }
+}
@@ -15,31 +15,42 @@ async function run() {
 
   console.log('Balance: ', balance.toHuman());
 
-  const sTokenContractFactory = await getContractFactory('erc20', signer);
-  const sTokenContract = await sTokenContractFactory.deployed('IErc20,new', '0', 'SToken', 'STO', '10', {
+  const assetContractFactory = await getContractFactory('erc20', signer);
+  const assetContract = await assetContractFactory.deployed('IErc20,new', '1000000000000000', 'Test DOT', 'TDOT', '10', {
     gasLimit: '200000000000',
     value: '10000000000000000',
   });
   console.log(
-    'Deploy sToken successfully. The contract address: ',
-    sTokenContract.address.toString()
+    'Deploy underlying asset successfully. The contract address: ',
+    assetContract.address.toString()
+  );
+  console.log('');
+
+  const oracleContractFactory = await getContractFactory('oracle', signer);
+  const oracleContract = await oracleContractFactory.deployed('new', {
+    gasLimit: '200000000000',
+    value: '10000000000000000',
+  });
+  console.log(
+    'Deploy price oracle successfully. The contract address: ',
+    oracleContract.address.toString()
   );
   console.log('');
 
-  const debtTokenContractFactory = await getContractFactory('erc20', signer);
-  const debtTokenContract = await debtTokenContractFactory.deployed('IErc20,new', '0', 'Debt Token', 'DTT', '10', {
+  const collectorContractFactory = await getContractFactory('collector', signer);
+  const collectorContract = await collectorContractFactory.deployed('new', {
     gasLimit: '200000000000',
     value: '10000000000000000',
   });
   console.log(
-    'Deploy debtToken successfully. The contract address: ',
-    debtTokenContract.address.toString()
+    'Deploy collector successfully. The contract address: ',
+    collectorContract.address.toString()
   );
   console.log('');
 
   const contractFactory = await getContractFactory('lendingpool', signer);
 
-  const contract = await contractFactory.deployed('new', sTokenContract.address, debtTokenContract.address, {
+  const contract = await contractFactory.deployed('new', oracleContract.address, collectorContract.address, {
     gasLimit: '200000000000',
     value: '10000000000000000',
   });
@@ -50,11 +61,65 @@ async function run() {
     contract.address.toString()
   );
 
-  // transfer stoken contract ownership to maker
-  await sTokenContract.tx['ownable,transferOwnership'](contract.address.toString());
+  const sTokenContractFactory = await getContractFactory('stoken', signer);
+  const sTokenContract = await sTokenContractFactory.deployed('new', contract.address, assetContract.address, 'SToken', 'STO', '10', {
+    gasLimit: '200000000000',
+    value: '10000000000000000',
+  });
+  console.log(
+    'Deploy sToken successfully. The contract address: ',
+    sTokenContract.address.toString()
+  );
+  console.log('');
 
-  // transfer debttoken contract ownership to maker
-  await debtTokenContract.tx['ownable,transferOwnership'](contract.address.toString())
+  const stableDebtTokenContractFactory = await getContractFactory('stabledebttoken', signer);
+  const stableDebtTokenContract = await stableDebtTokenContractFactory.deployed('new', contract.address, assetContract.address, 'Stable Debt Token', 'SDTT', '10', {
+    gasLimit: '200000000000',
+    value: '10000000000000000',
+  });
+  console.log(
+    'Deploy stableDebtToken successfully. The contract address: ',
+    stableDebtTokenContract.address.toString()
+  );
+  console.log('');
+
+  const variableDebtTokenContractFactory = await getContractFactory('variabledebttoken', signer);
+  const variableDebtTokenContract = await variableDebtTokenContractFactory.deployed('new', contract.address, assetContract.address, 'Variable Debt Token', 'VDTT', '10', {
+    gasLimit: '200000000000',
+    value: '10000000000000000',
+  });
+  console.log(
+    'Deploy variableDebtToken successfully. The contract address: ',
+    variableDebtTokenContract.address.toString()
+  );
+  console.log('');
+
+  // price of one TDOT in the base currency, in wad
+  await oracleContract.tx.setAssetPrice(assetContract.address, '1000000000000000000');
+
+  // list the underlying asset as a reserve
+  await contract.tx.initReserve(assetContract.address, sTokenContract.address, stableDebtTokenContract.address, variableDebtTokenContract.address, {
+    decimals: 10,
+    // rates in ray: 80% optimal utilisation, variable 0% base, 4% and 75% slopes,
+    // stable 3% base, 2% and 75% slopes
+    interestRateStrategy: {
+      optimalUtilizationRate: '800000000000000000000000000',
+      baseBorrowRate: '0',
+      rateSlope1: '40000000000000000000000000',
+      rateSlope2: '750000000000000000000000000',
+      baseStableBorrowRate: '30000000000000000000000000',
+      stableRateSlope1: '20000000000000000000000000',
+      stableRateSlope2: '750000000000000000000000000',
+    },
+    stableBorrowRateEnabled: true,
+    reserveFactor: 1000,
+    ltv: 7500,
+    liquidationThreshold: 8000,
+    liquidationBonus: 10500,
+    // in whole tokens, 0 for no cap
+    borrowCap: 0,
+    supplyCap: 0,
+  });
 
   api.disconnect();
 }
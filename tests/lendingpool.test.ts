@@ -0,0 +1,150 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('Lendingpool', () => {
+  before(async () => {
+    // another test file may have disconnected the shared api already
+    if (!api.isConnected) {
+      await api.connect();
+    }
+    await api.isReady;
+  });
+
+  after(() => {
+    return api.disconnect();
+  });
+
+  // rates in ray: 80% optimal utilisation, variable 0% base, 4% and 75% slopes,
+  // stable 3% base, 2% and 75% slopes
+  const interestRateStrategy = {
+    optimalUtilizationRate: '800000000000000000000000000',
+    baseBorrowRate: '0',
+    rateSlope1: '40000000000000000000000000',
+    rateSlope2: '750000000000000000000000000',
+    baseStableBorrowRate: '30000000000000000000000000',
+    stableRateSlope1: '20000000000000000000000000',
+    stableRateSlope2: '750000000000000000000000000'
+  };
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const admin = await getRandomSigner(Alice, one.muln(1000));
+    const lender = await getRandomSigner(Alice, one.muln(100));
+    const borrower = await getRandomSigner(Alice, one.muln(100));
+
+    const oracle = await (await getContractFactory('oracle', admin)).deploy('new');
+    const collector = await (await getContractFactory('collector', admin)).deploy('new');
+    const pool = await (await getContractFactory('lendingpool', admin)).deploy(
+      'new',
+      oracle.address,
+      collector.address
+    );
+
+    // `variableDebtTokensPool` lets a test hand the variable debt token to another pool than
+    // the one of the reserve, so that any mint or burn from the pool is rejected
+    async function listReserve(symbol: string, variableDebtTokensPool = pool.address) {
+      const asset = await (await getContractFactory('erc20', admin)).deploy(
+        'new',
+        '1000000000000000',
+        `Test ${symbol}`,
+        symbol,
+        '10'
+      );
+      const stoken = await (await getContractFactory('stoken', admin)).deploy(
+        'new',
+        pool.address,
+        asset.address,
+        `SToken ${symbol}`,
+        `s${symbol}`,
+        '10'
+      );
+      const stableDebtToken = await (await getContractFactory('stabledebttoken', admin)).deploy(
+        'new',
+        pool.address,
+        asset.address,
+        `Stable Debt ${symbol}`,
+        `sd${symbol}`,
+        '10'
+      );
+      const variableDebtToken = await (
+        await getContractFactory('variabledebttoken', admin)
+      ).deploy(
+        'new',
+        variableDebtTokensPool,
+        asset.address,
+        `Variable Debt ${symbol}`,
+        `vd${symbol}`,
+        '10'
+      );
+
+      await oracle.tx.setAssetPrice(asset.address, '1000000000000000000');
+      await pool.tx.initReserve(
+        asset.address,
+        stoken.address,
+        stableDebtToken.address,
+        variableDebtToken.address,
+        {
+          decimals: 10,
+          interestRateStrategy,
+          stableBorrowRateEnabled: true,
+          reserveFactor: 1000,
+          ltv: 7500,
+          liquidationThreshold: 8000,
+          liquidationBonus: 10500,
+          borrowCap: 0,
+          supplyCap: 0
+        }
+      );
+
+      return { asset, stoken, stableDebtToken, variableDebtToken };
+    }
+
+    async function deposit(signer: any, asset: any, amount: number) {
+      await asset.tx['psp22,transfer'](signer.address, amount, []);
+      await asset.tx['psp22,approve'](pool.address, amount, { signer });
+      await pool.tx.deposit(asset.address, amount, null, { signer });
+    }
+
+    return { admin, lender, borrower, pool, listReserve, deposit };
+  }
+
+  it('Swap reverts the burn when the new debt can not be minted', async () => {
+    const { admin, lender, borrower, pool, listReserve, deposit } = await setup();
+
+    const collateral = await listReserve('COL');
+    // the variable debt tokens only accept the admin as their pool, so the
+    // pool can burn the stable debt but not mint the variable one
+    const borrowed = await listReserve('BOR', admin.address);
+
+    await deposit(borrower, collateral.asset, 1000000);
+    await deposit(lender, borrowed.asset, 1000000);
+    await pool.tx.borrow(borrowed.asset.address, 100000, 'Stable', borrower.address, {
+      signer: borrower
+    });
+
+    const debtBefore = await borrowed.stableDebtToken.query.principalBalanceOf(borrower.address);
+    expect(debtBefore.output).to.equal(100000);
+
+    // the mint failure traps instead of returning an error after the burn
+    const result = await pool
+      .connect(borrower)
+      .query.swapBorrowRateMode(borrowed.asset.address, 'Stable');
+    expect(result.result.isErr).to.be.true;
+
+    await pool.tx
+      .swapBorrowRateMode(borrowed.asset.address, 'Stable', { signer: borrower })
+      .catch(() => undefined);
+
+    const debtAfter = await borrowed.stableDebtToken.query.principalBalanceOf(borrower.address);
+    expect(debtAfter.output).to.equal(100000);
+    const variableDebt = await borrowed.variableDebtToken.query.balanceOf(borrower.address);
+    expect(variableDebt.output).to.equal(0);
+  });
+});
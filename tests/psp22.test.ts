@@ -0,0 +1,190 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('Erc20', () => {
+  before(async () => {
+    // another test file may have disconnected the shared api already
+    if (!api.isConnected) {
+      await api.connect();
+    }
+    await api.isReady;
+  });
+
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(100));
+    const contractFactory = await getContractFactory('erc20', sender);
+    const contract = await contractFactory.deploy('new', '1000000', 'Jupiter Token', 'JPT', '10');
+    const receiver = await getRandomSigner(Alice, one.muln(10));
+
+    return { sender, contractFactory, contract, receiver, Alice, one };
+  }
+
+  describe('PSP22', () => {
+    it('Exposes the PSP22 metadata', async () => {
+      const { contract } = await setup();
+
+      expect((await contract.query['psp22Metadata,tokenName']()).output?.toString()).to.equal(
+        'Jupiter Token'
+      );
+      expect((await contract.query['psp22Metadata,tokenSymbol']()).output?.toString()).to.equal(
+        'JPT'
+      );
+      expect((await contract.query['psp22Metadata,tokenDecimals']()).output).to.equal(10);
+    });
+
+    it('Transfer with data adds amount to destination account', async () => {
+      const { contract, sender, receiver } = await setup();
+
+      await expect(contract.tx['psp22,transfer'](receiver.address, 7, []))
+        .to.emit(contract, 'Transfer')
+        .withArgs(sender.address, receiver.address, 7);
+
+      const result = await contract.query['psp22,balanceOf'](receiver.address);
+      expect(result.output).to.equal(7);
+    });
+
+    it('Increases and decreases the allowance', async () => {
+      const { contract, sender, receiver } = await setup();
+
+      await contract.tx['psp22,approve'](receiver.address, 7);
+      await contract.tx['psp22,increaseAllowance'](receiver.address, 5);
+      await contract.tx['psp22,decreaseAllowance'](receiver.address, 2);
+
+      const result = await contract.query['psp22,allowance'](sender.address, receiver.address);
+      expect(result.output).to.equal(10);
+    });
+
+    it('Can not decrease the allowance below 0', async () => {
+      const { contract, receiver } = await setup();
+
+      await contract.tx['psp22,approve'](receiver.address, 7);
+
+      const result = await contract.query['psp22,decreaseAllowance'](receiver.address, 8);
+      expect(result.output?.asErr.isInsufficientAllowance).to.be.true;
+    });
+
+    it('TransferFrom with data spends the allowance', async () => {
+      const { contract, sender, receiver } = await setup();
+
+      await contract.tx['psp22,approve'](receiver.address, 7);
+
+      const destination = await getRandomSigner();
+      await contract.tx['psp22,transferFrom'](sender.address, destination.address, 7, [], {
+        signer: receiver
+      });
+
+      const balance = await contract.query['psp22,balanceOf'](destination.address);
+      expect(balance.output).to.equal(7);
+      const allowance = await contract.query['psp22,allowance'](sender.address, receiver.address);
+      expect(allowance.output).to.equal(0);
+    });
+  });
+
+  describe('Pausable and blacklist', () => {
+    it('Can not transfer while paused', async () => {
+      const { contract, receiver } = await setup();
+
+      await contract.tx['pausable,pause']();
+
+      const result = await contract.query['psp22,transfer'](receiver.address, 7, []);
+      expect(result.output?.asErr.asCustom.toString()).to.equal('Paused');
+
+      const legacy = await contract.query['iErc20,transfer'](receiver.address, 7);
+      expect(legacy.output?.asErr.isPaused).to.be.true;
+    });
+
+    it('Can not transfer to a blacklisted account', async () => {
+      const { contract, receiver } = await setup();
+
+      await expect(contract.tx['blackList,addBlacklist'](receiver.address))
+        .to.emit(contract, 'AddedBlackList')
+        .withArgs(receiver.address);
+
+      const result = await contract.query['psp22,transfer'](receiver.address, 7, []);
+      expect(result.output?.asErr.asCustom.toString()).to.equal('BlacklistedUser');
+
+      const approval = await contract.query['psp22,approve'](receiver.address, 7);
+      expect(approval.output?.asErr.asCustom.toString()).to.equal('BlacklistedUser');
+    });
+  });
+
+  describe('Minting', () => {
+    // the all zero account
+    const ZERO_ADDRESS = '5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM';
+
+    it('Can not mint without the minter role', async () => {
+      const { contract, receiver } = await setup();
+
+      const result = await contract.connect(receiver).query.mint(receiver.address, 7);
+      expect(result.output?.asErr.isMissingRole).to.be.true;
+    });
+
+    it('Can not mint to the zero address', async () => {
+      const { contract } = await setup();
+
+      const result = await contract.query.mint(ZERO_ADDRESS, 7);
+      expect(result.output?.asErr.isZeroAddress).to.be.true;
+    });
+  });
+
+  describe('Roles and ownership', () => {
+    const MINTER = 1;
+
+    it('Mints once granted the minter role', async () => {
+      const { contract, sender, receiver } = await setup();
+
+      await expect(contract.tx['accessControl,grantRole'](MINTER, receiver.address))
+        .to.emit(contract, 'RoleGranted')
+        .withArgs(MINTER, receiver.address, sender.address);
+
+      await contract.tx.mint(receiver.address, 7, { signer: receiver });
+
+      const result = await contract.query['psp22,balanceOf'](receiver.address);
+      expect(result.output).to.equal(7);
+    });
+
+    it('Can not grant roles without being the owner', async () => {
+      const { contract, receiver } = await setup();
+
+      const result = await contract
+        .connect(receiver)
+        .query['accessControl,grantRole'](MINTER, receiver.address);
+      expect(result.output?.asErr.isOnlyOwnerAccess).to.be.true;
+    });
+
+    it('Transfers the ownership once accepted by the new owner', async () => {
+      const { contract, sender, receiver } = await setup();
+
+      await contract.tx['ownable,transferOwnership'](receiver.address);
+
+      let owner = await contract.query['ownable,owner']();
+      expect(owner.output?.toString()).to.equal(sender.address);
+
+      await expect(contract.tx['ownable,acceptOwnership']({ signer: receiver }))
+        .to.emit(contract, 'OwnershipTransferred')
+        .withArgs(sender.address, receiver.address);
+
+      owner = await contract.query['ownable,owner']();
+      expect(owner.output?.toString()).to.equal(receiver.address);
+    });
+
+    it('Can not accept the ownership without being the pending owner', async () => {
+      const { contract, receiver } = await setup();
+
+      const result = await contract.connect(receiver).query['ownable,acceptOwnership']();
+      expect(result.output?.asErr.isInvalidNewOwner).to.be.true;
+    });
+  });
+});